@@ -1,10 +1,31 @@
 use serde::Serialize;
+use std::path::PathBuf;
 use wayland_client::protocol::{wl_output::WlOutput, wl_seat::WlSeat, wl_shm::WlShm};
+use wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_list_v1::ExtForeignToplevelListV1;
+use wayland_protocols::ext::image_capture_source::v1::client::{
+    ext_foreign_toplevel_image_capture_source_manager_v1::ExtForeignToplevelImageCaptureSourceManagerV1,
+    ext_output_image_capture_source_manager_v1::ExtOutputImageCaptureSourceManagerV1,
+};
+use wayland_protocols::ext::image_copy_capture::v1::client::ext_image_copy_capture_manager_v1::ExtImageCopyCaptureManagerV1;
+use wayland_protocols::ext::workspace::v1::client::ext_workspace_manager_v1::ExtWorkspaceManagerV1;
+use wayland_protocols::wp::color_management::v1::client::wp_color_manager_v1::WpColorManagerV1;
+use wayland_protocols::wp::color_representation::v1::client::wp_color_representation_manager_v1::WpColorRepresentationManagerV1;
+use wayland_protocols::wp::drm_lease::v1::client::wp_drm_lease_device_v1::WpDrmLeaseDeviceV1;
+use wayland_protocols::wp::linux_dmabuf::zv1::client::zwp_linux_dmabuf_v1::ZwpLinuxDmabufV1;
 use wayland_protocols::wp::presentation_time::client::wp_presentation::WpPresentation;
+use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_manager_v2::ZwpTabletManagerV2;
 use wayland_protocols::xdg::xdg_output::zv1::client::{
     zxdg_output_manager_v1::ZxdgOutputManagerV1, zxdg_output_v1::ZxdgOutputV1,
 };
+use wayland_protocols_plasma::output_device::v2::client::kde_output_device_v2::KdeOutputDeviceV2;
+use wayland_protocols_plasma::output_order::v1::client::kde_output_order_v1::KdeOutputOrderV1;
+use wayland_protocols_plasma::plasma_virtual_desktop::client::org_kde_plasma_virtual_desktop_management::OrgKdePlasmaVirtualDesktopManagement;
+use wayland_protocols_plasma::plasma_window_management::client::org_kde_plasma_window_management::OrgKdePlasmaWindowManagement;
 use wayland_protocols_treeland::output_manager::v1::client::treeland_output_manager_v1::TreelandOutputManagerV1;
+use wayland_protocols_wlr::export_dmabuf::v1::client::zwlr_export_dmabuf_manager_v1::ZwlrExportDmabufManagerV1;
+use wayland_protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1;
+use wayland_protocols_wlr::output_management::v1::client::zwlr_output_manager_v1::ZwlrOutputManagerV1;
+use wayland_protocols_wlr::screencopy::v1::client::zwlr_screencopy_manager_v1::ZwlrScreencopyManagerV1;
 
 // Global info structure
 #[derive(Debug, Clone, Serialize)]
@@ -21,7 +42,27 @@ pub struct AppData {
     pub(crate) globals: Vec<GlobalInfo>,
     pub(crate) seats: Vec<crate::protocols::wl_seat::SeatInfo>,
     pub(crate) outputs: Vec<crate::protocols::wl_output::OutputInfo>,
+    pub(crate) wlr_output_managers:
+        Vec<crate::protocols::wlr_output_management::WlrOutputManagerInfo>,
+    pub(crate) kde_output_devices: Vec<crate::protocols::kde_output_device::KdeOutputDeviceInfo>,
+    pub(crate) kde_output_orders: Vec<crate::protocols::kde_output_order::KdeOutputOrderInfo>,
+    pub(crate) workspace_managers: Vec<crate::protocols::ext_workspace::WorkspaceManagerInfo>,
+    pub(crate) foreign_toplevel_lists:
+        Vec<crate::protocols::ext_foreign_toplevel_list::ForeignToplevelListInfo>,
+    pub(crate) wlr_toplevel_managers:
+        Vec<crate::protocols::wlr_foreign_toplevel::WlrToplevelManagerInfo>,
+    pub(crate) plasma_window_managers:
+        Vec<crate::protocols::plasma_window_management::PlasmaWindowManagementInfo>,
+    pub(crate) plasma_virtual_desktop_managers:
+        Vec<crate::protocols::plasma_virtual_desktop::PlasmaVirtualDesktopManagementInfo>,
+    pub(crate) tablet_managers: Vec<crate::protocols::wp_tablet::TabletManagerInfo>,
+    pub(crate) color_managers: Vec<crate::protocols::wp_color_management::ColorManagerInfo>,
+    pub(crate) color_representation_info:
+        Vec<crate::protocols::wp_color_representation::ColorRepresentationInfo>,
+    pub(crate) screencopy_managers: Vec<crate::protocols::wlr_screencopy::ScreencopyManagerInfo>,
     pub(crate) shm_info: Vec<crate::protocols::wl_shm::ShmInfo>,
+    pub(crate) dmabuf_info: Vec<crate::protocols::linux_dmabuf::DmabufInfo>,
+    pub(crate) wl_drm_info: Vec<crate::protocols::wl_drm::WlDrmInfo>,
     pub(crate) drm_lease_devices: Vec<crate::protocols::wp_drm_lease_device::DrmLeaseDeviceInfo>,
     pub(crate) presentation_info: Vec<crate::protocols::wp_presentation::PresentationInfo>,
     pub(crate) treeland_output_managers:
@@ -29,11 +70,36 @@ pub struct AppData {
     pub(crate) xdg_output_managers: Vec<crate::protocols::xdg_output::XdgOutputManagerInfo>,
     pub(crate) seat_objects: Vec<WlSeat>,
     pub(crate) output_objects: Vec<WlOutput>,
+    pub(crate) wlr_output_manager_objects: Vec<ZwlrOutputManagerV1>,
+    pub(crate) kde_output_device_objects: Vec<KdeOutputDeviceV2>,
+    pub(crate) kde_output_order_objects: Vec<KdeOutputOrderV1>,
+    pub(crate) workspace_manager_objects: Vec<ExtWorkspaceManagerV1>,
+    pub(crate) foreign_toplevel_list_objects: Vec<ExtForeignToplevelListV1>,
+    pub(crate) wlr_toplevel_manager_objects: Vec<ZwlrForeignToplevelManagerV1>,
+    pub(crate) plasma_window_manager_objects: Vec<OrgKdePlasmaWindowManagement>,
+    pub(crate) plasma_virtual_desktop_manager_objects: Vec<OrgKdePlasmaVirtualDesktopManagement>,
+    pub(crate) tablet_manager_objects: Vec<ZwpTabletManagerV2>,
+    pub(crate) color_manager_objects: Vec<WpColorManagerV1>,
+    pub(crate) color_representation_objects: Vec<WpColorRepresentationManagerV1>,
+    pub(crate) output_capture_source_manager_objects: Vec<ExtOutputImageCaptureSourceManagerV1>,
+    pub(crate) toplevel_capture_source_manager_objects:
+        Vec<ExtForeignToplevelImageCaptureSourceManagerV1>,
+    pub(crate) image_copy_capture_manager_objects: Vec<ExtImageCopyCaptureManagerV1>,
+    pub(crate) screencopy_manager_objects: Vec<ZwlrScreencopyManagerV1>,
+    pub(crate) export_dmabuf_manager_objects: Vec<ZwlrExportDmabufManagerV1>,
     pub(crate) shm_objects: Vec<WlShm>,
+    pub(crate) dmabuf_objects: Vec<ZwpLinuxDmabufV1>,
+    pub(crate) wl_drm_objects: Vec<crate::protocols::wl_drm::client::wl_drm::WlDrm>,
+    pub(crate) drm_lease_device_objects: Vec<WpDrmLeaseDeviceV1>,
     pub(crate) presentation_objects: Vec<WpPresentation>,
     pub(crate) treeland_output_manager_objects: Vec<TreelandOutputManagerV1>,
     pub(crate) xdg_output_manager_objects: Vec<ZxdgOutputManagerV1>,
     pub(crate) xdg_output_objects: Vec<ZxdgOutputV1>,
+    pub(crate) wlr_configuration_result:
+        Option<crate::protocols::wlr_output_management::ConfigurationResult>,
+    pub(crate) color_probe_results: Vec<crate::color_probe::ColorProbeResult>,
+    pub(crate) capture_probe_results: Vec<crate::capture_probe::CaptureProbeResult>,
+    pub(crate) sysfs_root: PathBuf,
     pending_events: usize,
 }
 
@@ -43,18 +109,55 @@ impl AppData {
             globals: Vec::new(),
             seats: Vec::new(),
             outputs: Vec::new(),
+            wlr_output_managers: Vec::new(),
+            kde_output_devices: Vec::new(),
+            kde_output_orders: Vec::new(),
+            workspace_managers: Vec::new(),
+            foreign_toplevel_lists: Vec::new(),
+            wlr_toplevel_managers: Vec::new(),
+            plasma_window_managers: Vec::new(),
+            plasma_virtual_desktop_managers: Vec::new(),
+            tablet_managers: Vec::new(),
+            color_managers: Vec::new(),
+            color_representation_info: Vec::new(),
+            screencopy_managers: Vec::new(),
             shm_info: Vec::new(),
+            dmabuf_info: Vec::new(),
+            wl_drm_info: Vec::new(),
             drm_lease_devices: Vec::new(),
             presentation_info: Vec::new(),
             treeland_output_managers: Vec::new(),
             xdg_output_managers: Vec::new(),
             seat_objects: Vec::new(),
             output_objects: Vec::new(),
+            wlr_output_manager_objects: Vec::new(),
+            kde_output_device_objects: Vec::new(),
+            kde_output_order_objects: Vec::new(),
+            workspace_manager_objects: Vec::new(),
+            foreign_toplevel_list_objects: Vec::new(),
+            wlr_toplevel_manager_objects: Vec::new(),
+            plasma_window_manager_objects: Vec::new(),
+            plasma_virtual_desktop_manager_objects: Vec::new(),
+            tablet_manager_objects: Vec::new(),
+            color_manager_objects: Vec::new(),
+            color_representation_objects: Vec::new(),
+            output_capture_source_manager_objects: Vec::new(),
+            toplevel_capture_source_manager_objects: Vec::new(),
+            image_copy_capture_manager_objects: Vec::new(),
+            screencopy_manager_objects: Vec::new(),
+            export_dmabuf_manager_objects: Vec::new(),
             shm_objects: Vec::new(),
+            dmabuf_objects: Vec::new(),
+            wl_drm_objects: Vec::new(),
+            drm_lease_device_objects: Vec::new(),
             presentation_objects: Vec::new(),
             treeland_output_manager_objects: Vec::new(),
             xdg_output_manager_objects: Vec::new(),
             xdg_output_objects: Vec::new(),
+            wlr_configuration_result: None,
+            color_probe_results: Vec::new(),
+            capture_probe_results: Vec::new(),
+            sysfs_root: PathBuf::from(crate::drm_device::DEFAULT_SYSFS_ROOT),
             pending_events: 0,
         }
     }
@@ -80,7 +183,8 @@ impl AppData {
     }
 }
 
-// Common user data type
+// Common user data type. Objects the compositor creates, or that are created
+// without an index, carry no data and are looked up in AppData by proxy.
 #[derive(Debug)]
 #[allow(dead_code)]
 pub enum UserData {
@@ -90,9 +194,111 @@ pub enum UserData {
     Output {
         output_index: usize,
     },
+    WlrOutputManager {
+        manager_index: usize,
+    },
+    WlrOutputHead,
+    WlrOutputMode,
+    WlrOutputConfiguration,
+    WlrOutputConfigurationHead,
+    KdeOutputDevice {
+        device_index: usize,
+    },
+    KdeOutputDeviceMode,
+    KdeOutputOrder {
+        order_index: usize,
+    },
+    WorkspaceManager {
+        manager_index: usize,
+    },
+    WorkspaceGroup,
+    Workspace,
+    ForeignToplevelList {
+        list_index: usize,
+    },
+    ForeignToplevel,
+    WlrToplevelManager {
+        manager_index: usize,
+    },
+    WlrToplevel,
+    PlasmaWindowManagement {
+        manager_index: usize,
+    },
+    PlasmaWindow,
+    PlasmaVirtualDesktopManagement {
+        manager_index: usize,
+    },
+    PlasmaVirtualDesktop,
+    TabletManager {
+        manager_index: usize,
+    },
+    TabletSeat {
+        manager_index: usize,
+        seat_index: usize,
+    },
+    Tablet,
+    TabletTool,
+    TabletPad,
+    TabletPadGroup,
+    TabletPadControl,
+    ColorManager {
+        manager_index: usize,
+    },
+    ColorManagementOutput {
+        output_index: usize,
+    },
+    ImageDescription {
+        output_index: usize,
+    },
+    ImageDescriptionInfo {
+        output_index: usize,
+    },
+    ColorProbeCreator,
+    ColorProbe {
+        probe_index: usize,
+    },
+    ColorRepresentation {
+        representation_index: usize,
+    },
+    ImageCaptureSourceManager,
+    ImageCaptureSource,
+    ImageCopyCaptureManager,
+    OutputCaptureSession {
+        output_index: usize,
+    },
+    ToplevelCaptureSession,
+    CaptureProbeSession {
+        probe_index: usize,
+    },
+    CaptureProbeFrame {
+        probe_index: usize,
+    },
+    ScreencopyManager {
+        manager_index: usize,
+    },
+    ScreencopyFrame {
+        manager_index: usize,
+        frame_index: usize,
+    },
+    ExportDmabufManager,
+    ExportDmabufFrame {
+        output_index: usize,
+    },
     Shm {
         shm_index: usize,
     },
+    ShmPool,
+    ShmBuffer,
+    LinuxDmabuf {
+        dmabuf_index: usize,
+    },
+    WlDrm {
+        drm_index: usize,
+    },
+    DrmLeaseDevice {
+        device_index: usize,
+    },
+    DrmLeaseConnector,
     Presentation {
         presentation_index: usize,
     },
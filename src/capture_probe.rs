@@ -0,0 +1,524 @@
+use memmap2::MmapMut;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::os::fd::{AsFd, FromRawFd, OwnedFd};
+use std::path::Path;
+use std::thread;
+use std::time::{Duration, Instant};
+use wayland_client::{
+    protocol::{wl_buffer::WlBuffer, wl_shm, wl_shm::WlShm, wl_shm_pool::WlShmPool},
+    EventQueue, QueueHandle,
+};
+use wayland_protocols::ext::image_copy_capture::v1::client::{
+    ext_image_copy_capture_frame_v1::ExtImageCopyCaptureFrameV1, ext_image_copy_capture_manager_v1,
+};
+use wayland_protocols_wlr::screencopy::v1::client::zwlr_screencopy_frame_v1::ZwlrScreencopyFrameV1;
+
+use crate::app::{AppData, UserData};
+use crate::protocols::ext_image_copy_capture::CaptureConstraintsInfo;
+use crate::protocols::wl_shm::format_to_fourcc;
+
+// Upper bound on the time spent waiting for buffer constraints and for each
+// captured frame; outputs still pending afterwards are reported as failed
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+const EXT_IMAGE_COPY_CAPTURE: &str = "ext-image-copy-capture";
+const WLR_SCREENCOPY: &str = "wlr-screencopy";
+
+// Buffer allocated for one captured frame
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureProbeBuffer {
+    pub(crate) format: u32,
+    pub(crate) fourcc: String,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) stride: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationTime {
+    pub(crate) seconds: u64,
+    pub(crate) nanoseconds: u32,
+}
+
+impl PresentationTime {
+    pub(crate) fn new(tv_sec_hi: u32, tv_sec_lo: u32, tv_nsec: u32) -> Self {
+        Self {
+            seconds: ((tv_sec_hi as u64) << 32) | tv_sec_lo as u64,
+            nanoseconds: tv_nsec,
+        }
+    }
+}
+
+// Outcome of capturing one output
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureProbeResult {
+    // ext-image-copy-capture session, or the wlr-screencopy frame as fallback
+    #[serde(skip_serializing)]
+    pub(crate) session: Option<CaptureConstraintsInfo>,
+    #[serde(skip_serializing)]
+    pub(crate) screencopy_frame: Option<ZwlrScreencopyFrameV1>,
+    // Frames are taken and destroyed once the compositor reports ready or failed
+    #[serde(skip_serializing)]
+    pub(crate) capture_frame: Option<ExtImageCopyCaptureFrameV1>,
+    // Set once the wlr-screencopy frame has sent all buffer parameters
+    #[serde(skip_serializing)]
+    pub(crate) buffer_done: bool,
+    pub(crate) output: String,
+    pub(crate) protocol: String,
+    pub(crate) status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) failure: Option<String>,
+    pub(crate) buffer: Option<CaptureProbeBuffer>,
+    pub(crate) flags: Vec<String>,
+    pub(crate) transform: Option<String>,
+    pub(crate) presentation_time: Option<PresentationTime>,
+    pub(crate) image: Option<String>,
+}
+
+impl CaptureProbeResult {
+    pub(crate) fn fail(&mut self, failure: impl Into<String>) {
+        self.status = "failed".to_string();
+        self.failure = Some(failure.into());
+    }
+
+    // Buffer parameters are known (or the request already failed)
+    fn constraints_known(&self) -> bool {
+        match &self.session {
+            Some(session) => session.state != "pending",
+            None => self.buffer_done || self.status != "pending",
+        }
+    }
+}
+
+// Outcome of a capture probe run, printed by the output module
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureProbeReport {
+    pub(crate) results: Vec<CaptureProbeResult>,
+}
+
+impl CaptureProbeReport {
+    pub(crate) fn succeeded(&self) -> bool {
+        self.results.iter().all(|result| result.status == "ready")
+    }
+}
+
+// memfd-backed wl_shm buffer that a frame is copied into
+struct ShmBuffer {
+    pool: WlShmPool,
+    buffer: WlBuffer,
+    map: MmapMut,
+}
+
+// Capture one frame of every output into a wl_shm buffer, using
+// ext-image-copy-capture when available and wlr-screencopy otherwise.
+// With `image_dir`, each ready frame is written to <dir>/<output-name>.ppm.
+pub(crate) fn probe_capture(
+    event_queue: &mut EventQueue<AppData>,
+    app_data: &mut AppData,
+    image_dir: Option<&Path>,
+) -> Result<CaptureProbeReport, String> {
+    let shm = app_data
+        .shm_objects
+        .first()
+        .cloned()
+        .ok_or("Compositor does not support wl_shm")?;
+    let shm_formats: Vec<u32> = app_data
+        .shm_info
+        .first()
+        .map(|info| info.formats.iter().map(|f| f.format).collect())
+        .unwrap_or_default();
+    let use_ext = !app_data.output_capture_source_manager_objects.is_empty()
+        && !app_data.image_copy_capture_manager_objects.is_empty();
+    if !use_ext && app_data.screencopy_manager_objects.is_empty() {
+        return Err(
+            "Compositor supports neither ext-image-copy-capture nor wlr-screencopy".to_string(),
+        );
+    }
+    if app_data.output_objects.is_empty() {
+        return Err("Compositor has no outputs to capture".to_string());
+    }
+    if let Some(dir) = image_dir {
+        fs::create_dir_all(dir)
+            .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+    }
+
+    let qh = event_queue.handle();
+    app_data.capture_probe_results.clear();
+    start_captures(app_data, &qh, use_ext);
+    dispatch_until(event_queue, app_data, CaptureProbeResult::constraints_known)?;
+
+    // Allocate a buffer of the advertised format and request the copy
+    let mut buffers: Vec<Option<ShmBuffer>> = Vec::new();
+    for probe_index in 0..app_data.capture_probe_results.len() {
+        let buffer = match app_data.capture_probe_results[probe_index].session.as_ref() {
+            Some(_) => capture_ext_frame(app_data, &shm, &shm_formats, &qh, probe_index),
+            None => capture_screencopy_frame(app_data, &shm, &shm_formats, &qh, probe_index),
+        };
+        let result = &mut app_data.capture_probe_results[probe_index];
+        match buffer {
+            Ok(buffer) => buffers.push(buffer),
+            Err(err) => {
+                result.fail(err);
+                buffers.push(None);
+            }
+        }
+    }
+    dispatch_until(event_queue, app_data, |result| result.status != "pending")?;
+
+    let mut results = std::mem::take(&mut app_data.capture_probe_results);
+    for (result, buffer) in results.iter_mut().zip(buffers) {
+        // Frames still in flight must go before the buffer they copy into
+        if let Some(frame) = result.capture_frame.take() {
+            frame.destroy();
+        }
+        if let Some(frame) = result.screencopy_frame.take() {
+            frame.destroy();
+        }
+        if let Some(mut session) = result.session.take().filter(|s| s.state != "stopped") {
+            session.finish("done");
+        }
+        if result.status == "pending" {
+            result.fail("timed out waiting for the frame");
+        }
+        let Some(shm_buffer) = buffer else {
+            continue;
+        };
+        if let (Some(dir), "ready") = (image_dir, result.status.as_str()) {
+            let path = dir.join(format!("{}.ppm", result.output));
+            write_ppm(&path, &shm_buffer.map, result)?;
+            result.image = Some(path.display().to_string());
+        }
+        shm_buffer.buffer.destroy();
+        shm_buffer.pool.destroy();
+    }
+
+    Ok(CaptureProbeReport { results })
+}
+
+fn start_captures(app_data: &mut AppData, qh: &QueueHandle<AppData>, use_ext: bool) {
+    for (output_index, output) in app_data.output_objects.iter().enumerate() {
+        let probe_index = app_data.capture_probe_results.len();
+        let (session, screencopy_frame) = if use_ext {
+            let source = app_data.output_capture_source_manager_objects[0].create_source(
+                output,
+                qh,
+                UserData::ImageCaptureSource,
+            );
+            let session = app_data.image_copy_capture_manager_objects[0].create_session(
+                &source,
+                ext_image_copy_capture_manager_v1::Options::empty(),
+                qh,
+                UserData::CaptureProbeSession { probe_index },
+            );
+            (Some(CaptureConstraintsInfo::new(source, session)), None)
+        } else {
+            let frame = app_data.screencopy_manager_objects[0].capture_output(
+                0,
+                output,
+                qh,
+                UserData::CaptureProbeFrame { probe_index },
+            );
+            (None, Some(frame))
+        };
+        app_data.capture_probe_results.push(CaptureProbeResult {
+            session,
+            screencopy_frame,
+            capture_frame: None,
+            buffer_done: false,
+            output: app_data
+                .outputs
+                .get(output_index)
+                .map(|info| info.output_name.clone())
+                .unwrap_or_default(),
+            protocol: if use_ext {
+                EXT_IMAGE_COPY_CAPTURE
+            } else {
+                WLR_SCREENCOPY
+            }
+            .to_string(),
+            status: "pending".to_string(),
+            failure: None,
+            buffer: None,
+            flags: Vec::new(),
+            transform: None,
+            presentation_time: None,
+            image: None,
+        });
+    }
+}
+
+// Dispatch events until every result satisfies `finished` or the timeout expires
+fn dispatch_until(
+    event_queue: &mut EventQueue<AppData>,
+    app_data: &mut AppData,
+    finished: impl Fn(&CaptureProbeResult) -> bool,
+) -> Result<(), String> {
+    let deadline = Instant::now() + CAPTURE_TIMEOUT;
+    loop {
+        event_queue
+            .roundtrip(app_data)
+            .map_err(|e| format!("Wayland roundtrip failed: {}", e))?;
+        if app_data.capture_probe_results.iter().all(&finished) || Instant::now() >= deadline {
+            return Ok(());
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+}
+
+fn capture_ext_frame(
+    app_data: &mut AppData,
+    shm: &WlShm,
+    shm_formats: &[u32],
+    qh: &QueueHandle<AppData>,
+    probe_index: usize,
+) -> Result<Option<ShmBuffer>, String> {
+    let result = &mut app_data.capture_probe_results[probe_index];
+    let Some(session) = result.session.as_ref() else {
+        return Ok(None);
+    };
+    match session.state.as_str() {
+        "done" => {}
+        "stopped" => return Err("capture session stopped".to_string()),
+        _ => return Err("timed out waiting for buffer constraints".to_string()),
+    }
+    let size = session
+        .buffer_size
+        .as_ref()
+        .ok_or("compositor sent no buffer size")?;
+    let format = session
+        .shm_formats
+        .iter()
+        .map(|f| f.format)
+        .find(|format| shm_formats.contains(format) && rgb_offsets(*format).is_some())
+        .ok_or("no common 32-bit RGB shm format")?;
+    let buffer = CaptureProbeBuffer {
+        format,
+        fourcc: format_to_fourcc(format),
+        width: size.width,
+        height: size.height,
+        stride: size.width * 4,
+    };
+    let shm_buffer = create_shm_buffer(shm, qh, &buffer)?;
+
+    let frame = session
+        .session
+        .create_frame(qh, UserData::CaptureProbeFrame { probe_index });
+    frame.attach_buffer(&shm_buffer.buffer);
+    frame.damage_buffer(0, 0, buffer.width as i32, buffer.height as i32);
+    frame.capture();
+    result.capture_frame = Some(frame);
+    result.buffer = Some(buffer);
+    Ok(Some(shm_buffer))
+}
+
+fn capture_screencopy_frame(
+    app_data: &mut AppData,
+    shm: &WlShm,
+    shm_formats: &[u32],
+    qh: &QueueHandle<AppData>,
+    probe_index: usize,
+) -> Result<Option<ShmBuffer>, String> {
+    let result = &mut app_data.capture_probe_results[probe_index];
+    if result.status != "pending" {
+        return Ok(None);
+    }
+    if !result.buffer_done {
+        return Err("timed out waiting for buffer parameters".to_string());
+    }
+    let Some(frame) = result.screencopy_frame.as_ref() else {
+        return Ok(None);
+    };
+    let buffer = result
+        .buffer
+        .as_ref()
+        .ok_or("compositor sent no shm buffer")?;
+    if !shm_formats.contains(&buffer.format) || rgb_offsets(buffer.format).is_none() {
+        return Err(format!("unsupported shm format {}", buffer.fourcc));
+    }
+    let shm_buffer = create_shm_buffer(shm, qh, buffer)?;
+    frame.copy(&shm_buffer.buffer);
+    Ok(Some(shm_buffer))
+}
+
+fn create_shm_buffer(
+    shm: &WlShm,
+    qh: &QueueHandle<AppData>,
+    buffer: &CaptureProbeBuffer,
+) -> Result<ShmBuffer, String> {
+    let format = wl_shm::Format::try_from(buffer.format)
+        .map_err(|_| format!("unknown shm format {}", buffer.fourcc))?;
+    let size = buffer.stride as usize * buffer.height as usize;
+
+    // SAFETY: memfd_create takes a NUL-terminated name and returns a new fd or -1.
+    let fd = unsafe { libc::memfd_create(c"wayland-info-capture".as_ptr(), libc::MFD_CLOEXEC) };
+    if fd < 0 {
+        return Err(format!(
+            "memfd_create failed: {}",
+            std::io::Error::last_os_error()
+        ));
+    }
+    // SAFETY: fd was just created and is owned by nothing else.
+    let file = File::from(unsafe { OwnedFd::from_raw_fd(fd) });
+    file.set_len(size as u64)
+        .map_err(|err| format!("Failed to size shm buffer: {}", err))?;
+    // SAFETY: the memfd is private to this process and the compositor only writes to it.
+    let map = unsafe { MmapMut::map_mut(&file) }
+        .map_err(|err| format!("Failed to map shm buffer: {}", err))?;
+
+    let pool = shm.create_pool(file.as_fd(), size as i32, qh, UserData::ShmPool);
+    let wl_buffer = pool.create_buffer(
+        0,
+        buffer.width as i32,
+        buffer.height as i32,
+        buffer.stride as i32,
+        format,
+        qh,
+        UserData::ShmBuffer,
+    );
+    Ok(ShmBuffer {
+        pool,
+        buffer: wl_buffer,
+        map,
+    })
+}
+
+// Byte offsets of red, green and blue within a little-endian 32-bit pixel
+fn rgb_offsets(format: u32) -> Option<[usize; 3]> {
+    match wl_shm::Format::try_from(format).ok()? {
+        wl_shm::Format::Argb8888 | wl_shm::Format::Xrgb8888 => Some([2, 1, 0]),
+        wl_shm::Format::Abgr8888 | wl_shm::Format::Xbgr8888 => Some([0, 1, 2]),
+        _ => None,
+    }
+}
+
+// Write a captured frame as a binary PPM, honouring the y_invert flag
+fn write_ppm(path: &Path, pixels: &[u8], result: &CaptureProbeResult) -> Result<(), String> {
+    let Some(buffer) = &result.buffer else {
+        return Ok(());
+    };
+    let Some([r, g, b]) = rgb_offsets(buffer.format) else {
+        return Ok(());
+    };
+    let (width, height, stride) = (
+        buffer.width as usize,
+        buffer.height as usize,
+        buffer.stride as usize,
+    );
+    let y_invert = result.flags.iter().any(|flag| flag == "y_invert");
+
+    let mut data = format!("P6\n{} {}\n255\n", width, height).into_bytes();
+    data.reserve(width * height * 3);
+    for y in 0..height {
+        let row = if y_invert { height - 1 - y } else { y };
+        let row = &pixels[row * stride..row * stride + width * 4];
+        for pixel in row.chunks_exact(4) {
+            data.extend_from_slice(&[pixel[r], pixel[g], pixel[b]]);
+        }
+    }
+    fs::write(path, data).map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Ready result for a 2x2 frame whose rows are padded to a 20-byte stride
+    fn result_for(format: wl_shm::Format, flags: &[&str]) -> CaptureProbeResult {
+        CaptureProbeResult {
+            session: None,
+            screencopy_frame: None,
+            capture_frame: None,
+            buffer_done: true,
+            output: "DP-1".to_string(),
+            protocol: WLR_SCREENCOPY.to_string(),
+            status: "ready".to_string(),
+            failure: None,
+            buffer: Some(CaptureProbeBuffer {
+                format: format as u32,
+                fourcc: format_to_fourcc(format as u32),
+                width: 2,
+                height: 2,
+                stride: 20,
+            }),
+            flags: flags.iter().map(|flag| flag.to_string()).collect(),
+            transform: None,
+            presentation_time: None,
+            image: None,
+        }
+    }
+
+    // Little-endian XRGB pixels are stored as B, G, R, X
+    const PIXELS: [u8; 40] = [
+        0x00, 0x00, 0xff, 0xff, 0x00, 0xff, 0x00, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, //
+        0xff, 0x00, 0x00, 0xff, 0x10, 0x20, 0x30, 0xff, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    ];
+
+    fn ppm(name: &str, result: &CaptureProbeResult) -> Vec<u8> {
+        let path = std::env::temp_dir().join(format!(
+            "wayland-info-capture-{}-{}.ppm",
+            name,
+            std::process::id()
+        ));
+        write_ppm(&path, &PIXELS, result).unwrap();
+        let data = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        data
+    }
+
+    #[test]
+    fn writes_rows_top_down_without_padding() {
+        let data = ppm("plain", &result_for(wl_shm::Format::Xrgb8888, &[]));
+        let (header, pixels) = data.split_at(11);
+        assert_eq!(header, b"P6\n2 2\n255\n");
+        assert_eq!(
+            pixels,
+            [0xff, 0, 0, 0, 0xff, 0, 0, 0, 0xff, 0x30, 0x20, 0x10]
+        );
+    }
+
+    #[test]
+    fn y_invert_flips_rows() {
+        let data = ppm(
+            "inverted",
+            &result_for(wl_shm::Format::Xrgb8888, &["y_invert"]),
+        );
+        assert_eq!(
+            &data[11..],
+            [0, 0, 0xff, 0x30, 0x20, 0x10, 0xff, 0, 0, 0, 0xff, 0]
+        );
+    }
+
+    #[test]
+    fn byte_order_follows_the_shm_format() {
+        assert_eq!(
+            rgb_offsets(wl_shm::Format::Argb8888 as u32),
+            Some([2, 1, 0])
+        );
+        assert_eq!(
+            rgb_offsets(wl_shm::Format::Xbgr8888 as u32),
+            Some([0, 1, 2])
+        );
+        assert_eq!(rgb_offsets(wl_shm::Format::Rgb565 as u32), None);
+        assert_eq!(rgb_offsets(wl_shm::Format::Xrgb2101010 as u32), None);
+
+        let data = ppm("abgr", &result_for(wl_shm::Format::Abgr8888, &[]));
+        assert_eq!(&data[11..14], [0, 0, 0xff]);
+    }
+
+    #[test]
+    fn presentation_time_joins_the_seconds_halves() {
+        let time = PresentationTime::new(1, 2, 999_999_999);
+        assert_eq!(time.seconds, (1 << 32) | 2);
+        assert_eq!(time.nanoseconds, 999_999_999);
+        assert_eq!(
+            PresentationTime::new(0, u32::MAX, 0).seconds,
+            u32::MAX as u64
+        );
+    }
+}
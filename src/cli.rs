@@ -19,6 +19,45 @@ pub struct Cli {
     #[argh(option, short = 'p')]
     pub protocol: Option<String>,
 
+    /// sysfs root used to resolve DRM devices (default: /sys)
+    #[argh(
+        option,
+        default = "String::from(crate::drm_device::DEFAULT_SYSFS_ROOT)"
+    )]
+    pub sysfs_root: String,
+
+    /// test an output layout file without applying it
+    #[argh(option)]
+    pub test_layout: Option<String>,
+
+    /// apply an output layout file, reverting unless confirmed
+    #[argh(option)]
+    pub apply_layout: Option<String>,
+
+    /// seconds to wait for confirmation after --apply-layout (default: 15)
+    #[argh(option, default = "15")]
+    pub confirm_timeout: u64,
+
+    /// probe which parametric color descriptions the compositor accepts
+    #[argh(switch)]
+    pub probe_color: bool,
+
+    /// capture one frame of every output to check that screen capture works
+    #[argh(switch)]
+    pub probe_capture: bool,
+
+    /// write frames captured by --probe-capture to <dir>/<output-name>.ppm
+    #[argh(option)]
+    pub capture_dir: Option<String>,
+
+    /// write each seat's raw keymap to <dir>/<seat-name>.xkb
+    #[argh(option)]
+    pub dump_keymaps: Option<String>,
+
+    /// write each output's ICC profile to <dir>/<output-name>.icc
+    #[argh(option)]
+    pub export_icc: Option<String>,
+
     /// print version information and exit
     #[argh(switch, short = 'v')]
     pub version: bool,
@@ -29,6 +68,15 @@ pub struct CliOptions {
     pub full_output: bool,
     pub sort_output: bool,
     pub protocol_filter: Option<String>,
+    pub sysfs_root: String,
+    pub test_layout: Option<String>,
+    pub apply_layout: Option<String>,
+    pub confirm_timeout: u64,
+    pub probe_color: bool,
+    pub probe_capture: bool,
+    pub capture_dir: Option<String>,
+    pub dump_keymaps: Option<String>,
+    pub export_icc: Option<String>,
 }
 
 pub fn parse_args() -> CliOptions {
@@ -46,5 +94,14 @@ pub fn parse_args() -> CliOptions {
         full_output,
         sort_output: cli.sort,
         protocol_filter: cli.protocol,
+        sysfs_root: cli.sysfs_root,
+        test_layout: cli.test_layout,
+        apply_layout: cli.apply_layout,
+        confirm_timeout: cli.confirm_timeout,
+        probe_color: cli.probe_color,
+        probe_capture: cli.probe_capture,
+        capture_dir: cli.capture_dir,
+        dump_keymaps: cli.dump_keymaps,
+        export_icc: cli.export_icc,
     }
 }
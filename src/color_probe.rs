@@ -0,0 +1,164 @@
+use serde::Serialize;
+use wayland_client::{EventQueue, WEnum};
+use wayland_protocols::wp::color_management::v1::client::wp_image_description_v1::WpImageDescriptionV1;
+
+use crate::app::{AppData, UserData};
+use crate::protocols::wp_color_management::{primaries_to_string, transfer_function_to_string};
+
+// Upper bound on roundtrips spent waiting for ready/failed; descriptions still
+// pending afterwards are reported as such
+const MAX_PROBE_ROUNDTRIPS: usize = 20;
+
+// Luminances as sent by set_luminances: min cd/m2 * 10000, max cd/m2, reference cd/m2
+type ProbeLuminances = (u32, u32, u32);
+
+// Luminance variants tried for each combination; None keeps the TF defaults
+const LUMINANCE_VARIANTS: [(&str, Option<ProbeLuminances>); 3] = [
+    ("default", None),
+    ("sdr", Some((2000, 80, 80))),
+    ("hdr", Some((50, 1000, 203))),
+];
+
+// Outcome of one parametric image description
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorProbeResult {
+    pub(crate) transfer_function: String,
+    pub(crate) primaries: String,
+    pub(crate) luminances: String,
+    pub(crate) status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) failure: Option<String>,
+}
+
+// Outcome of a color management probe run, printed by the output module
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorProbeReport {
+    pub(crate) transfer_functions: Vec<String>,
+    pub(crate) primaries: Vec<String>,
+    pub(crate) luminances: Vec<String>,
+    pub(crate) results: Vec<ColorProbeResult>,
+}
+
+impl ColorProbeReport {
+    pub(crate) fn succeeded(&self) -> bool {
+        self.results.iter().all(|result| result.status == "ready")
+    }
+}
+
+// Create a parametric image description for every advertised transfer function
+// and primaries combination and record whether the compositor accepts it
+pub(crate) fn probe_color_management(
+    event_queue: &mut EventQueue<AppData>,
+    app_data: &mut AppData,
+) -> Result<ColorProbeReport, String> {
+    let manager = app_data
+        .color_manager_objects
+        .first()
+        .cloned()
+        .ok_or("Compositor does not support wp_color_manager_v1")?;
+    let manager_info = app_data.color_managers[0].clone();
+    if !manager_info.features.iter().any(|f| f == "parametric") {
+        return Err("Compositor does not support parametric image descriptions".to_string());
+    }
+    // Luminance variants need set_luminances; sending it otherwise is a protocol error
+    let variants: Vec<_> = if manager_info.features.iter().any(|f| f == "set_luminances") {
+        LUMINANCE_VARIANTS.to_vec()
+    } else {
+        LUMINANCE_VARIANTS[..1].to_vec()
+    };
+
+    let qh = event_queue.handle();
+    let mut descriptions: Vec<WpImageDescriptionV1> = Vec::new();
+    app_data.color_probe_results.clear();
+
+    for &tf in &manager_info.named_transfer_functions {
+        for &primaries in &manager_info.named_primaries {
+            for &(label, luminances) in &variants {
+                let probe_index = app_data.color_probe_results.len();
+                app_data.color_probe_results.push(ColorProbeResult {
+                    transfer_function: transfer_function_to_string(WEnum::Value(tf)),
+                    primaries: primaries_to_string(WEnum::Value(primaries)),
+                    luminances: label.to_string(),
+                    status: "pending".to_string(),
+                    failure: None,
+                });
+
+                let creator = manager.create_parametric_creator(&qh, UserData::ColorProbeCreator);
+                creator.set_tf_named(tf);
+                creator.set_primaries_named(primaries);
+                if let Some((min, max, reference)) = luminances {
+                    creator.set_luminances(min, max, reference);
+                }
+                descriptions.push(creator.create(&qh, UserData::ColorProbe { probe_index }));
+            }
+        }
+    }
+
+    for _ in 0..MAX_PROBE_ROUNDTRIPS {
+        event_queue
+            .roundtrip(app_data)
+            .map_err(|e| format!("Wayland roundtrip failed: {}", e))?;
+        if app_data
+            .color_probe_results
+            .iter()
+            .all(|result| result.status != "pending")
+        {
+            break;
+        }
+    }
+
+    for description in descriptions {
+        description.destroy();
+    }
+
+    Ok(ColorProbeReport {
+        transfer_functions: manager_info.transfer_functions,
+        primaries: manager_info.primaries,
+        luminances: variants
+            .iter()
+            .map(|(label, _)| label.to_string())
+            .collect(),
+        results: std::mem::take(&mut app_data.color_probe_results),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // set_luminances raises invalid_luminance unless max and reference exceed min
+    #[test]
+    fn luminance_variants_are_valid_for_set_luminances() {
+        for (label, luminances) in LUMINANCE_VARIANTS {
+            if let Some((min, max, reference)) = luminances {
+                let min = min as f64 / 10_000.0;
+                assert!(max as f64 > min, "{}", label);
+                assert!(reference as f64 > min, "{}", label);
+            }
+        }
+    }
+
+    #[test]
+    fn report_succeeds_only_when_every_description_is_ready() {
+        let result = |status: &str| ColorProbeResult {
+            transfer_function: "st2084_pq".to_string(),
+            primaries: "bt2020".to_string(),
+            luminances: "hdr".to_string(),
+            status: status.to_string(),
+            failure: None,
+        };
+        let mut report = ColorProbeReport {
+            transfer_functions: Vec::new(),
+            primaries: Vec::new(),
+            luminances: Vec::new(),
+            results: vec![result("ready"), result("ready")],
+        };
+        assert!(report.succeeded());
+        report.results.push(result("pending"));
+        assert!(!report.succeeded());
+        report.results[2] = result("failed");
+        assert!(!report.succeeded());
+    }
+}
@@ -0,0 +1,122 @@
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+// Default sysfs mount point used to resolve DRM device numbers
+pub const DEFAULT_SYSFS_ROOT: &str = "/sys";
+
+// DRM device info structure, resolved from a dev_t through sysfs
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DrmDevice {
+    pub(crate) major: u64,
+    pub(crate) minor: u64,
+    pub(crate) node: Option<String>,
+    pub(crate) driver: Option<String>,
+    pub(crate) vendor_id: Option<u32>,
+    pub(crate) device_id: Option<u32>,
+    pub(crate) boot_vga: Option<bool>,
+}
+
+impl DrmDevice {
+    // Resolve a dev_t via <sysfs_root>/dev/char/MAJ:MIN
+    pub(crate) fn from_dev_t(dev: u64, sysfs_root: &Path) -> Self {
+        let major = ((dev >> 32) & 0xffff_f000) | ((dev >> 8) & 0x0fff);
+        let minor = ((dev >> 12) & 0xffff_ff00) | (dev & 0x00ff);
+        let sysfs_dir = sysfs_root.join(format!("dev/char/{}:{}", major, minor));
+        let pci_dir = sysfs_dir.join("device");
+
+        Self {
+            major,
+            minor,
+            node: read_uevent_devname(&sysfs_dir),
+            driver: fs::read_link(pci_dir.join("driver"))
+                .ok()
+                .and_then(|link| link.file_name().map(|n| n.to_string_lossy().to_string())),
+            vendor_id: read_hex_attribute(&pci_dir.join("vendor")),
+            device_id: read_hex_attribute(&pci_dir.join("device")),
+            boot_vga: fs::read_to_string(pci_dir.join("boot_vga"))
+                .ok()
+                .map(|value| value.trim() == "1"),
+        }
+    }
+
+    // Decode a dev_t sent as a native-endian byte array
+    pub(crate) fn from_dev_t_bytes(bytes: &[u8], sysfs_root: &Path) -> Option<Self> {
+        let dev = u64::from_ne_bytes(bytes.try_into().ok()?);
+        Some(Self::from_dev_t(dev, sysfs_root))
+    }
+}
+
+// Read the device node name (e.g. "renderD128") from the sysfs uevent file
+fn read_uevent_devname(sysfs_dir: &Path) -> Option<String> {
+    let uevent = fs::read_to_string(sysfs_dir.join("uevent")).ok()?;
+    uevent
+        .lines()
+        .find_map(|line| line.strip_prefix("DEVNAME="))
+        .map(|devname| devname.rsplit('/').next().unwrap_or(devname).to_string())
+}
+
+// Parse a sysfs attribute such as "0x8086"
+fn read_hex_attribute(path: &Path) -> Option<u32> {
+    let value = fs::read_to_string(path).ok()?;
+    let value = value.trim();
+    u32::from_str_radix(value.strip_prefix("0x").unwrap_or(value), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn fixture_root() -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/sys")
+    }
+
+    #[test]
+    fn render_node_bytes_resolve_against_fixture() {
+        let dev = libc::makedev(226, 128);
+        let device = DrmDevice::from_dev_t_bytes(&dev.to_ne_bytes(), &fixture_root()).unwrap();
+        assert_eq!((device.major, device.minor), (226, 128));
+        assert_eq!(device.node.as_deref(), Some("renderD128"));
+        assert_eq!(device.driver.as_deref(), Some("i915"));
+        assert_eq!(device.vendor_id, Some(0x8086));
+        assert_eq!(device.device_id, Some(0x46a6));
+        assert_eq!(device.boot_vga, Some(true));
+    }
+
+    #[test]
+    fn primary_node_resolves_against_fixture() {
+        let device = DrmDevice::from_dev_t(libc::makedev(226, 0), &fixture_root());
+        assert_eq!(device.node.as_deref(), Some("card0"));
+        assert_eq!(device.driver.as_deref(), Some("i915"));
+    }
+
+    // Numbers above 255/4095 use the high bits of the glibc dev_t encoding;
+    // the node has a uevent but no PCI parent, as for a virtual device
+    #[test]
+    fn large_device_numbers_and_partial_sysfs() {
+        let root = std::env::temp_dir().join(format!("wayland-info-sysfs-{}", std::process::id()));
+        let node_dir = root.join("dev/char/4097:300");
+        fs::create_dir_all(&node_dir).unwrap();
+        fs::write(
+            node_dir.join("uevent"),
+            "MAJOR=4097\nMINOR=300\nDEVNAME=dri/card44\n",
+        )
+        .unwrap();
+
+        let device = DrmDevice::from_dev_t(libc::makedev(4097, 300), &root);
+        fs::remove_dir_all(&root).unwrap();
+
+        assert_eq!((device.major, device.minor), (4097, 300));
+        assert_eq!(device.node.as_deref(), Some("card44"));
+        assert!(device.driver.is_none());
+        assert!(device.vendor_id.is_none() && device.device_id.is_none());
+        assert!(device.boot_vga.is_none());
+    }
+
+    #[test]
+    fn dev_t_must_be_eight_bytes() {
+        assert!(DrmDevice::from_dev_t_bytes(&[0; 4], &fixture_root()).is_none());
+    }
+}
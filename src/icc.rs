@@ -0,0 +1,94 @@
+// ICC profile header size; the tag table follows it
+const HEADER_SIZE: usize = 128;
+// Size of one tag table entry: signature, offset, size
+const TAG_ENTRY_SIZE: usize = 12;
+
+// Read the profile description ('desc' tag) from an ICC profile.
+// Handles both the v2 textDescriptionType and the v4 multiLocalizedUnicodeType.
+pub(crate) fn profile_description(data: &[u8]) -> Option<String> {
+    let tag_count = read_u32(data, HEADER_SIZE)? as usize;
+    let tag = (0..tag_count).find_map(|i| {
+        let entry = HEADER_SIZE + 4 + i * TAG_ENTRY_SIZE;
+        if data.get(entry..entry + 4)? != b"desc" {
+            return None;
+        }
+        let offset = read_u32(data, entry + 4)? as usize;
+        let size = read_u32(data, entry + 8)? as usize;
+        data.get(offset..offset.checked_add(size)?)
+    })?;
+
+    match tag.get(0..4)? {
+        b"desc" => {
+            let count = read_u32(tag, 8)? as usize;
+            let text = tag.get(12..12 + count)?;
+            let text = text.split(|&b| b == 0).next().unwrap_or_default();
+            Some(String::from_utf8_lossy(text).into_owned())
+        }
+        b"mluc" => {
+            // Use the first localized record
+            if read_u32(tag, 8)? == 0 {
+                return None;
+            }
+            let length = read_u32(tag, 20)? as usize;
+            let offset = read_u32(tag, 24)? as usize;
+            let units: Vec<u16> = tag
+                .get(offset..offset.checked_add(length)?)?
+                .chunks_exact(2)
+                .map(|c| u16::from_be_bytes([c[0], c[1]]))
+                .collect();
+            Some(
+                String::from_utf16_lossy(&units)
+                    .trim_end_matches('\0')
+                    .to_string(),
+            )
+        }
+        _ => None,
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes(bytes.try_into().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Small monitor profiles carrying only desc, cprt and wtpt tags
+    const SRGB_V2: &[u8] = include_bytes!("../fixtures/icc/srgb-v2.icc");
+    const DISPLAY_P3_V4: &[u8] = include_bytes!("../fixtures/icc/display-p3-v4.icc");
+
+    #[test]
+    fn reads_v2_text_description() {
+        assert_eq!(
+            profile_description(SRGB_V2).as_deref(),
+            Some("sRGB IEC61966-2.1")
+        );
+    }
+
+    #[test]
+    fn reads_first_v4_localized_record() {
+        // The deDE record follows enUS and is not used
+        assert_eq!(
+            profile_description(DISPLAY_P3_V4).as_deref(),
+            Some("Display P3")
+        );
+    }
+
+    #[test]
+    fn profile_without_desc_tag_has_no_description() {
+        let mut profile = SRGB_V2.to_vec();
+        // Rename the second tag table entry, which points at the desc tag
+        let entry = HEADER_SIZE + 4 + TAG_ENTRY_SIZE;
+        assert_eq!(&profile[entry..entry + 4], b"desc");
+        profile[entry..entry + 4].copy_from_slice(b"dscm");
+        assert!(profile_description(&profile).is_none());
+    }
+
+    #[test]
+    fn truncated_profile_is_rejected() {
+        assert!(profile_description(&DISPLAY_P3_V4[..DISPLAY_P3_V4.len() / 2]).is_none());
+        assert!(profile_description(&SRGB_V2[..HEADER_SIZE]).is_none());
+    }
+}
@@ -0,0 +1,140 @@
+use serde::Serialize;
+
+// Symbol files that xkbcommon adds for the model rather than for layouts
+const MODEL_SYMBOLS: [&str; 2] = ["pc", "inet"];
+
+// Map option symbol files back to their rules option group
+const OPTION_GROUPS: [(&str, &str); 20] = [
+    ("group", "grp"),
+    ("ctrl", "ctrl"),
+    ("compose", "compose"),
+    ("level3", "lv3"),
+    ("level5", "lv5"),
+    ("terminate", "terminate"),
+    ("altwin", "altwin"),
+    ("capslock", "caps"),
+    ("srvr_ctrl", "srvrkeys"),
+    ("eurosign", "eurosign"),
+    ("keypad", "keypad"),
+    ("kpdl", "kpdl"),
+    ("nbsp", "nbsp"),
+    ("shift", "shift"),
+    ("japan", "japan"),
+    ("korean", "korean"),
+    ("parens", "parens"),
+    ("scrolllock", "scrolllock"),
+    ("rupeesign", "rupeesign"),
+    ("mod_led", "mod_led"),
+];
+
+// Layout summary recovered from a compiled XKB keymap. The model only selects
+// geometry and a few symbol files, and xkbcommon never serializes a geometry
+// section, so it cannot be recovered.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XkbSummary {
+    pub(crate) layouts: Vec<String>,
+    pub(crate) variants: Vec<String>,
+    pub(crate) options: Vec<String>,
+}
+
+// Summarize a keymap from the name of its xkb_symbols section,
+// e.g. xkb_symbols "pc+us+de(nodeadkeys):2+inet(evdev)+group(alt_shift_toggle)"
+pub(crate) fn parse_xkb(text: &str) -> XkbSummary {
+    let mut summary = XkbSummary::default();
+
+    if let Some(symbols) = section_name(text, "xkb_symbols") {
+        for part in symbols.split('+') {
+            // Drop the group index suffix, e.g. "de(nodeadkeys):2"
+            let part = part.split(':').next().unwrap_or(part);
+            let (file, variant) = split_variant(part);
+            if file.is_empty() {
+                continue;
+            }
+            if let Some((_, group)) = OPTION_GROUPS.iter().find(|(name, _)| *name == file) {
+                if let Some(variant) = variant {
+                    summary.options.push(format!("{}:{}", group, variant));
+                }
+            } else if !MODEL_SYMBOLS.contains(&file) {
+                summary.layouts.push(file.to_string());
+                summary
+                    .variants
+                    .push(variant.unwrap_or_default().to_string());
+            }
+        }
+    }
+
+    summary
+}
+
+// Return the quoted name following a section keyword
+fn section_name<'a>(text: &'a str, section: &str) -> Option<&'a str> {
+    let start = text.find(section)? + section.len();
+    let rest = text[start..].trim_start();
+    let rest = rest.strip_prefix('"')?;
+    rest.find('"').map(|end| &rest[..end])
+}
+
+// Split "file(variant)" into its file and optional variant
+fn split_variant(part: &str) -> (&str, Option<&str>) {
+    match part.split_once('(') {
+        Some((file, variant)) => (file, Some(variant.trim_end_matches(')'))),
+        None => (part, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Three layouts with group suffixes and two options, as xkbcommon serializes it
+    const US_DE_RU: &str = include_str!("../fixtures/keymaps/us-de-ru.xkb");
+
+    #[test]
+    fn summarizes_serialized_keymap() {
+        let summary = parse_xkb(US_DE_RU);
+        assert_eq!(summary.layouts, ["us", "de", "ru"]);
+        assert_eq!(summary.variants, ["", "nodeadkeys", "phonetic"]);
+        assert_eq!(summary.options, ["grp:alt_shift_toggle", "ctrl:nocaps"]);
+    }
+
+    #[test]
+    fn keycodes_section_does_not_leak_into_layouts() {
+        // "evdev+aliases(qwerty)" precedes the symbols and must be skipped
+        let summary = parse_xkb(US_DE_RU);
+        assert!(!summary
+            .layouts
+            .iter()
+            .any(|l| l == "aliases" || l == "evdev"));
+    }
+
+    #[test]
+    fn option_without_variant_is_not_reported() {
+        let summary = parse_xkb(r#"xkb_symbols "pc+gb+inet(evdev)+compose" { };"#);
+        assert_eq!(summary.layouts, ["gb"]);
+        assert!(summary.options.is_empty());
+    }
+
+    #[test]
+    fn options_keep_their_rules_group() {
+        let summary = parse_xkb(
+            r#"xkb_symbols "pc+us+inet(evdev)+shift(both_capslock)+kpdl(dot)+keypad(pointerkeys)+srvr_ctrl(fkey2vt)" { };"#,
+        );
+        assert_eq!(summary.layouts, ["us"]);
+        assert_eq!(
+            summary.options,
+            [
+                "shift:both_capslock",
+                "kpdl:dot",
+                "keypad:pointerkeys",
+                "srvrkeys:fkey2vt"
+            ]
+        );
+    }
+
+    #[test]
+    fn unnamed_symbols_section_yields_nothing() {
+        let summary = parse_xkb("xkb_keymap {\nxkb_symbols {\n};\n};\n");
+        assert!(summary.layouts.is_empty() && summary.options.is_empty());
+    }
+}
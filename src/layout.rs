@@ -0,0 +1,581 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use wayland_client::{EventQueue, QueueHandle};
+use wayland_protocols_wlr::output_management::v1::client::{
+    zwlr_output_configuration_v1::ZwlrOutputConfigurationV1,
+    zwlr_output_manager_v1::ZwlrOutputManagerV1,
+};
+
+use crate::app::{AppData, UserData};
+use crate::protocols::wl_output::transform_from_str;
+use crate::protocols::wlr_output_management::{
+    ConfigurationResult, WlrOutputHeadInfo, WlrOutputManagerInfo, WlrOutputModeInfo,
+};
+
+// Number of times a configuration is retried when the compositor cancels it
+const MAX_CONFIGURATION_ATTEMPTS: usize = 3;
+
+// Declarative output layout, keyed by output name
+#[derive(Debug, Deserialize)]
+#[serde(transparent)]
+pub struct OutputLayout {
+    pub(crate) outputs: BTreeMap<String, OutputLayoutEntry>,
+}
+
+// Desired state of a single output
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct OutputLayoutEntry {
+    #[serde(default = "default_enabled")]
+    pub(crate) enabled: bool,
+    pub(crate) mode: Option<String>,
+    pub(crate) x: Option<i32>,
+    pub(crate) y: Option<i32>,
+    pub(crate) scale: Option<f64>,
+    pub(crate) transform: Option<String>,
+}
+
+// Requested mode parsed from "WIDTHxHEIGHT" or "WIDTHxHEIGHT@REFRESH"
+#[derive(Debug, Clone, Copy)]
+struct LayoutMode {
+    width: i32,
+    height: i32,
+    refresh_mhz: Option<i32>,
+}
+
+// Outcome of a configuration run, printed by the output module
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigurationReport {
+    pub(crate) action: String,
+    pub(crate) attempts: Vec<ConfigurationResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) confirmed: Option<bool>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) revert_attempts: Vec<ConfigurationResult>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ConfigurationReport {
+    pub(crate) fn succeeded(&self) -> bool {
+        self.attempts.last() == Some(&ConfigurationResult::Succeeded)
+            && self.confirmed != Some(false)
+    }
+}
+
+pub(crate) fn load_layout(path: &str) -> Result<OutputLayout, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("Failed to read layout {}: {}", path, e))?;
+    serde_json::from_str(&contents).map_err(|e| format!("Invalid layout {}: {}", path, e))
+}
+
+fn parse_mode(mode: &str) -> Result<LayoutMode, String> {
+    let invalid = || format!("Invalid mode '{}', expected WIDTHxHEIGHT[@REFRESH]", mode);
+    let (size, refresh) = match mode.split_once('@') {
+        Some((size, refresh)) => (size, Some(refresh)),
+        None => (mode, None),
+    };
+    let (width, height) = size.split_once('x').ok_or_else(invalid)?;
+    let refresh_mhz = match refresh {
+        Some(refresh) => {
+            let hz: f64 = refresh
+                .trim_end_matches("Hz")
+                .parse()
+                .map_err(|_| invalid())?;
+            Some((hz * 1000.0).round() as i32)
+        }
+        None => None,
+    };
+    Ok(LayoutMode {
+        width: width.parse().map_err(|_| invalid())?,
+        height: height.parse().map_err(|_| invalid())?,
+        refresh_mhz,
+    })
+}
+
+// Pick the advertised mode closest to the request, preferring the preferred mode
+fn find_mode(modes: &[WlrOutputModeInfo], requested: LayoutMode) -> Option<&WlrOutputModeInfo> {
+    let candidates = modes
+        .iter()
+        .filter(|m| m.width == requested.width && m.height == requested.height);
+    match requested.refresh_mhz {
+        Some(refresh) => candidates
+            .filter(|m| (m.refresh - refresh).abs() <= 500)
+            .min_by_key(|m| (m.refresh - refresh).abs()),
+        None => candidates.max_by_key(|m| (m.preferred, m.refresh)),
+    }
+}
+
+impl OutputLayout {
+    // Capture the current head state as a layout that can be re-applied
+    pub(crate) fn from_heads(heads: &[WlrOutputHeadInfo]) -> Self {
+        let outputs = heads
+            .iter()
+            .map(|head| {
+                let mode =
+                    head.modes.iter().find(|m| m.current).map(|m| {
+                        format!("{}x{}@{:.3}", m.width, m.height, m.refresh as f64 / 1000.0)
+                    });
+                let entry = OutputLayoutEntry {
+                    enabled: head.enabled,
+                    mode,
+                    x: Some(head.x),
+                    y: Some(head.y),
+                    scale: Some(head.scale),
+                    transform: Some(head.transform.clone()),
+                };
+                (head.name.clone(), entry)
+            })
+            .collect();
+        Self { outputs }
+    }
+
+    // Check that every output in the layout exists and every value parses
+    pub(crate) fn validate(&self, heads: &[WlrOutputHeadInfo]) -> Result<(), String> {
+        for (name, entry) in &self.outputs {
+            if !heads.iter().any(|head| &head.name == name) {
+                return Err(format!("Unknown output in layout: {}", name));
+            }
+            if let Some(mode) = &entry.mode {
+                parse_mode(mode)?;
+            }
+            if let Some(transform) = &entry.transform {
+                transform_from_str(transform)
+                    .ok_or_else(|| format!("Invalid transform '{}' for {}", transform, name))?;
+            }
+            if entry.scale.is_some_and(|scale| scale <= 0.0) {
+                return Err(format!("Invalid scale for {}", name));
+            }
+        }
+        Ok(())
+    }
+
+    // Describe the layout as a zwlr_output_configuration_v1. Heads that are not
+    // listed keep their current enabled state and properties.
+    pub(crate) fn build_configuration(
+        &self,
+        manager: &ZwlrOutputManagerV1,
+        serial: u32,
+        heads: &[WlrOutputHeadInfo],
+        qh: &QueueHandle<AppData>,
+    ) -> ZwlrOutputConfigurationV1 {
+        let configuration =
+            manager.create_configuration(serial, qh, UserData::WlrOutputConfiguration);
+        for head in heads {
+            let entry = self.outputs.get(&head.name);
+            let enabled = entry.map_or(head.enabled, |entry| entry.enabled);
+            if !enabled {
+                configuration.disable_head(&head.head);
+                continue;
+            }
+
+            let config_head =
+                configuration.enable_head(&head.head, qh, UserData::WlrOutputConfigurationHead);
+            let Some(entry) = entry else {
+                continue;
+            };
+            if let Some(mode) = entry.mode.as_deref().and_then(|m| parse_mode(m).ok()) {
+                match find_mode(&head.modes, mode) {
+                    Some(found) => config_head.set_mode(&found.mode),
+                    None => config_head.set_custom_mode(
+                        mode.width,
+                        mode.height,
+                        mode.refresh_mhz.unwrap_or(0),
+                    ),
+                }
+            }
+            if entry.x.is_some() || entry.y.is_some() {
+                config_head.set_position(entry.x.unwrap_or(head.x), entry.y.unwrap_or(head.y));
+            }
+            if let Some(scale) = entry.scale {
+                config_head.set_scale(scale);
+            }
+            if let Some(transform) = entry.transform.as_deref().and_then(transform_from_str) {
+                config_head.set_transform(transform);
+            }
+        }
+        configuration
+    }
+}
+
+// Ask the compositor whether the layout would be accepted, without applying it
+pub(crate) fn test_layout(
+    event_queue: &mut EventQueue<AppData>,
+    app_data: &mut AppData,
+    layout: &OutputLayout,
+) -> Result<ConfigurationReport, String> {
+    let attempts = run_configuration(event_queue, app_data, layout, false)?;
+    Ok(ConfigurationReport {
+        action: "test".to_string(),
+        attempts,
+        confirmed: None,
+        revert_attempts: Vec::new(),
+    })
+}
+
+// Apply the layout and revert to the state captured at startup unless the
+// user confirms it within `timeout`
+pub(crate) fn apply_layout(
+    event_queue: &mut EventQueue<AppData>,
+    app_data: &mut AppData,
+    layout: &OutputLayout,
+    timeout: Duration,
+) -> Result<ConfigurationReport, String> {
+    let previous =
+        output_manager(app_data).map(|(_, info)| OutputLayout::from_heads(&info.heads))?;
+
+    let mut report = ConfigurationReport {
+        action: "apply".to_string(),
+        attempts: run_configuration(event_queue, app_data, layout, true)?,
+        confirmed: None,
+        revert_attempts: Vec::new(),
+    };
+    if report.attempts.last() != Some(&ConfigurationResult::Succeeded) {
+        return Ok(report);
+    }
+
+    let confirmed = wait_for_confirmation(timeout);
+    report.confirmed = Some(confirmed);
+    if !confirmed {
+        // Pick up the serial of the applied configuration before reverting.
+        event_queue
+            .roundtrip(app_data)
+            .map_err(|e| format!("Wayland roundtrip failed: {}", e))?;
+        report.revert_attempts = run_configuration(event_queue, app_data, &previous, true)?;
+    }
+
+    Ok(report)
+}
+
+// Send the layout as a configuration, retrying while the compositor cancels it
+fn run_configuration(
+    event_queue: &mut EventQueue<AppData>,
+    app_data: &mut AppData,
+    layout: &OutputLayout,
+    apply: bool,
+) -> Result<Vec<ConfigurationResult>, String> {
+    let (manager, manager_info) = output_manager(app_data)?;
+    layout.validate(&manager_info.heads)?;
+
+    let qh = event_queue.handle();
+    let mut attempts = Vec::new();
+
+    for _ in 0..MAX_CONFIGURATION_ATTEMPTS {
+        let (_, manager_info) = output_manager(app_data)?;
+        let serial = manager_info
+            .serial
+            .ok_or("zwlr_output_manager_v1 has not sent a configuration serial")?;
+        let configuration = layout.build_configuration(&manager, serial, &manager_info.heads, &qh);
+        if apply {
+            configuration.apply();
+        } else {
+            configuration.test();
+        }
+
+        let result = wait_for_configuration_result(event_queue, app_data)?;
+        configuration.destroy();
+        attempts.push(result);
+        if result != ConfigurationResult::Cancelled {
+            break;
+        }
+
+        // A cancelled configuration is outdated; pick up the new serial and retry.
+        event_queue
+            .roundtrip(app_data)
+            .map_err(|e| format!("Wayland roundtrip failed: {}", e))?;
+    }
+
+    Ok(attempts)
+}
+
+// Pair the first output manager proxy with its state; the registry pushes
+// both when it binds the global
+fn output_manager(
+    app_data: &AppData,
+) -> Result<(ZwlrOutputManagerV1, &WlrOutputManagerInfo), String> {
+    app_data
+        .wlr_output_manager_objects
+        .iter()
+        .zip(&app_data.wlr_output_managers)
+        .next()
+        .map(|(manager, info)| (manager.clone(), info))
+        .ok_or_else(|| "Compositor does not support zwlr_output_manager_v1".to_string())
+}
+
+// Prompt on stderr so stdout stays parsable; EOF on stdin counts as a refusal
+fn wait_for_confirmation(timeout: Duration) -> bool {
+    eprint!(
+        "Keep this configuration? [y/N] (reverting in {} s) ",
+        timeout.as_secs()
+    );
+    let _ = io::stderr().flush();
+
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut answer = String::new();
+        let confirmed = io::stdin()
+            .read_line(&mut answer)
+            .is_ok_and(|read| read > 0)
+            && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes");
+        let _ = sender.send(confirmed);
+    });
+
+    let confirmed = receiver.recv_timeout(timeout).unwrap_or(false);
+    if !confirmed {
+        eprintln!();
+    }
+    confirmed
+}
+
+fn wait_for_configuration_result(
+    event_queue: &mut EventQueue<AppData>,
+    app_data: &mut AppData,
+) -> Result<ConfigurationResult, String> {
+    app_data.wlr_configuration_result = None;
+    loop {
+        if let Some(result) = app_data.wlr_configuration_result.take() {
+            return Ok(result);
+        }
+        event_queue
+            .blocking_dispatch(app_data)
+            .map_err(|e| format!("Wayland dispatch failed: {}", e))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::net::UnixStream;
+    use std::path::PathBuf;
+    use wayland_client::{Connection, Proxy};
+    use wayland_protocols_wlr::output_management::v1::client::{
+        zwlr_output_head_v1::ZwlrOutputHeadV1, zwlr_output_mode_v1::ZwlrOutputModeV1,
+    };
+
+    // Heads and modes are never sent requests here, so inert proxies on an
+    // unconnected socket stand in for the compositor's objects
+    fn connection() -> Connection {
+        let (socket, _peer) = UnixStream::pair().unwrap();
+        Connection::from_socket(socket).unwrap()
+    }
+
+    fn mode(
+        conn: &Connection,
+        size: (i32, i32),
+        refresh: i32,
+        preferred: bool,
+    ) -> WlrOutputModeInfo {
+        WlrOutputModeInfo {
+            mode: ZwlrOutputModeV1::inert(conn.backend().downgrade()),
+            width: size.0,
+            height: size.1,
+            refresh,
+            preferred,
+            current: false,
+        }
+    }
+
+    fn head(conn: &Connection, name: &str) -> WlrOutputHeadInfo {
+        WlrOutputHeadInfo {
+            head: ZwlrOutputHeadV1::inert(conn.backend().downgrade()),
+            name: name.to_string(),
+            description: String::new(),
+            make: String::new(),
+            model: String::new(),
+            serial_number: String::new(),
+            physical_width: 0,
+            physical_height: 0,
+            enabled: true,
+            x: 0,
+            y: 0,
+            transform: "normal".to_string(),
+            scale: 1.0,
+            adaptive_sync: None,
+            modes: Vec::new(),
+        }
+    }
+
+    fn write_layout(name: &str, contents: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "wayland-info-layout-{}-{}.json",
+            name,
+            std::process::id()
+        ));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn loads_layout_with_defaults() {
+        let path = write_layout(
+            "defaults",
+            r#"{
+                "eDP-1": {"enabled": false},
+                "DP-1": {"mode": "2560x1440@143.998", "x": 1920, "y": 0, "scale": 1.25, "transform": "90"}
+            }"#,
+        );
+        let layout = load_layout(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let external = &layout.outputs["DP-1"];
+        assert!(external.enabled);
+        assert_eq!(external.mode.as_deref(), Some("2560x1440@143.998"));
+        assert_eq!((external.x, external.y), (Some(1920), Some(0)));
+        assert_eq!(external.scale, Some(1.25));
+        assert_eq!(external.transform.as_deref(), Some("90"));
+
+        let internal = &layout.outputs["eDP-1"];
+        assert!(!internal.enabled);
+        assert!(internal.mode.is_none() && internal.x.is_none() && internal.scale.is_none());
+    }
+
+    #[test]
+    fn misspelled_field_is_rejected() {
+        let path = write_layout("typo", r#"{"DP-1": {"posX": 1920}}"#);
+        let error = load_layout(path.to_str().unwrap()).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(error.starts_with("Invalid layout"), "{}", error);
+        assert!(error.contains("unknown field `posX`"), "{}", error);
+    }
+
+    #[test]
+    fn missing_layout_file_is_reported() {
+        let error = load_layout("/nonexistent/layout.json").unwrap_err();
+        assert!(error.starts_with("Failed to read layout /nonexistent/layout.json"));
+    }
+
+    #[test]
+    fn mode_strings_convert_refresh_to_millihertz() {
+        let mode = parse_mode("1920x1080").unwrap();
+        assert_eq!(
+            (mode.width, mode.height, mode.refresh_mhz),
+            (1920, 1080, None)
+        );
+        assert_eq!(parse_mode("1920x1080@60").unwrap().refresh_mhz, Some(60000));
+        assert_eq!(
+            parse_mode("3840x2160@59.94Hz").unwrap().refresh_mhz,
+            Some(59940)
+        );
+        assert_eq!(
+            parse_mode("2560x1440@143.9985").unwrap().refresh_mhz,
+            Some(143999)
+        );
+
+        for invalid in ["1920", "1920x", "x1080", "1920x1080@", "1920x1080@fast"] {
+            assert!(parse_mode(invalid).is_err(), "{} should not parse", invalid);
+        }
+    }
+
+    #[test]
+    fn find_mode_matches_refresh_within_half_a_hertz() {
+        let conn = connection();
+        let modes = [
+            mode(&conn, (1920, 1080), 60000, false),
+            mode(&conn, (1920, 1080), 59940, false),
+            mode(&conn, (1920, 1080), 144000, false),
+        ];
+        let refresh =
+            |requested| find_mode(&modes, parse_mode(requested).unwrap()).map(|m| m.refresh);
+        assert_eq!(refresh("1920x1080@60"), Some(60000));
+        assert_eq!(refresh("1920x1080@59.94"), Some(59940));
+        assert_eq!(refresh("1920x1080@143.6"), Some(144000));
+        assert_eq!(refresh("1920x1080@75"), None);
+        assert_eq!(refresh("1280x720@60"), None);
+    }
+
+    #[test]
+    fn find_mode_without_refresh_prefers_the_preferred_mode() {
+        let conn = connection();
+        let modes = [
+            mode(&conn, (2560, 1440), 59951, true),
+            mode(&conn, (2560, 1440), 165000, false),
+            mode(&conn, (1920, 1080), 60000, false),
+            mode(&conn, (1920, 1080), 120000, false),
+        ];
+        let pick = |requested| find_mode(&modes, parse_mode(requested).unwrap()).unwrap();
+        assert_eq!(pick("2560x1440").refresh, 59951);
+        // Without a preferred mode at that size the fastest one wins
+        assert_eq!(pick("1920x1080").refresh, 120000);
+    }
+
+    #[test]
+    fn validate_names_the_offending_output() {
+        let conn = connection();
+        let heads = [head(&conn, "DP-1")];
+        let layout = |json| OutputLayout {
+            outputs: serde_json::from_str(json).unwrap(),
+        };
+
+        assert!(
+            layout(r#"{"DP-1": {"mode": "1920x1080", "transform": "flipped-90"}}"#)
+                .validate(&heads)
+                .is_ok()
+        );
+        assert_eq!(
+            layout(r#"{"HDMI-A-1": {}}"#).validate(&heads),
+            Err("Unknown output in layout: HDMI-A-1".to_string())
+        );
+        assert_eq!(
+            layout(r#"{"DP-1": {"transform": "sideways"}}"#).validate(&heads),
+            Err("Invalid transform 'sideways' for DP-1".to_string())
+        );
+        assert_eq!(
+            layout(r#"{"DP-1": {"scale": 0}}"#).validate(&heads),
+            Err("Invalid scale for DP-1".to_string())
+        );
+        assert!(layout(r#"{"DP-1": {"mode": "big"}}"#)
+            .validate(&heads)
+            .is_err());
+    }
+
+    // apply_layout reverts to from_heads(); the captured layout must pass
+    // validation and pick each head's current mode again, not a near neighbour
+    #[test]
+    fn captured_layout_reselects_current_modes() {
+        let conn = connection();
+        let mut laptop = head(&conn, "eDP-1");
+        laptop.modes = vec![
+            mode(&conn, (1920, 1200), 60000, true),
+            mode(&conn, (1920, 1200), 59997, false),
+            mode(&conn, (1920, 1200), 59950, false),
+        ];
+        laptop.modes[1].current = true;
+        let mut external = head(&conn, "DP-1");
+        external.enabled = false;
+        external.x = 1920;
+        external.scale = 1.5;
+        external.transform = "flipped-270".to_string();
+        external.modes = vec![
+            mode(&conn, (3840, 2160), 143998, false),
+            mode(&conn, (3840, 2160), 144000, false),
+        ];
+        external.modes[0].current = true;
+        let heads = [laptop, external];
+
+        let layout = OutputLayout::from_heads(&heads);
+        layout.validate(&heads).unwrap();
+
+        for head in &heads {
+            let entry = &layout.outputs[&head.name];
+            assert_eq!(entry.enabled, head.enabled);
+            assert_eq!((entry.x, entry.scale), (Some(head.x), Some(head.scale)));
+            assert_eq!(entry.transform.as_deref(), Some(head.transform.as_str()));
+
+            let requested = parse_mode(entry.mode.as_deref().unwrap()).unwrap();
+            let selected = find_mode(&head.modes, requested).unwrap();
+            assert!(
+                selected.current,
+                "{} reselected {} mHz",
+                head.name, selected.refresh
+            );
+        }
+    }
+}
@@ -1,15 +1,27 @@
 mod app;
+mod capture_probe;
 mod cli;
+mod color_probe;
+mod drm_device;
+mod icc;
+mod keymap;
+mod layout;
 mod output;
 mod protocols;
 
 use colored::Colorize;
 use std::env;
+use std::path::Path;
+use std::time::Duration;
 use wayland_client::Connection;
 
 use crate::app::AppData;
 use crate::cli::parse_args;
-use crate::output::{print_all_info, print_basic_info, to_json_basic, to_json_output};
+use crate::output::{
+    print_all_info, print_basic_info, print_capture_probe_report, print_color_probe_report,
+    print_configuration_report, to_json_basic, to_json_capture_probe, to_json_color_probe,
+    to_json_configuration, to_json_output,
+};
 
 fn main() {
     let options = parse_args();
@@ -30,6 +42,7 @@ fn main() {
     let qh = event_queue.handle();
 
     let mut app_data = AppData::new();
+    app_data.sysfs_root = options.sysfs_root.into();
 
     let _registry = display.get_registry(&qh, ());
 
@@ -68,6 +81,14 @@ fn main() {
         }
     }
 
+    // Tablet seats, output image descriptions, capture sessions and capture
+    // frames need the seats and outputs bound during the initial roundtrip.
+    app_data.get_tablet_seats(&qh);
+    app_data.get_color_management_outputs(&qh);
+    app_data.get_output_capture_sessions(&qh);
+    app_data.get_screencopy_frames(&qh);
+    app_data.get_export_dmabuf_frames(&qh);
+
     let seat_objects: Vec<_> = app_data.seat_objects.drain(..).collect();
     for (index, seat) in seat_objects.iter().enumerate() {
         let seat_data = app::UserData::Seat { seat_index: index };
@@ -94,6 +115,95 @@ fn main() {
         }
     }
 
+    // The toplevel snapshot is complete; stop the lists and wait for `finished`.
+    app_data.stop_foreign_toplevel_lists();
+    event_queue
+        .roundtrip(&mut app_data)
+        .expect("Wayland roundtrip failed while stopping toplevel lists");
+
+    app_data.resolve_workspaces();
+    app_data.resolve_wlr_toplevels();
+    app_data.resolve_tablet_seats();
+    app_data.resolve_screencopy_frames();
+
+    if let Some(dir) = options.dump_keymaps.as_deref() {
+        if let Err(err) = app_data.dump_keymaps(Path::new(dir)) {
+            eprintln!("{}", err.red());
+        }
+    }
+
+    if let Some(dir) = options.export_icc.as_deref() {
+        if let Err(err) = app_data.export_icc_profiles(Path::new(dir)) {
+            eprintln!("{}", err.red());
+        }
+    }
+
+    let layout_report = if let Some(path) = options.apply_layout.as_deref() {
+        let timeout = Duration::from_secs(options.confirm_timeout);
+        Some(layout::load_layout(path).and_then(|layout| {
+            layout::apply_layout(&mut event_queue, &mut app_data, &layout, timeout)
+        }))
+    } else {
+        options.test_layout.as_deref().map(|path| {
+            layout::load_layout(path)
+                .and_then(|layout| layout::test_layout(&mut event_queue, &mut app_data, &layout))
+        })
+    };
+
+    if let Some(report) = layout_report {
+        let report = report.unwrap_or_else(|err| {
+            eprintln!("{}", err.red());
+            std::process::exit(2);
+        });
+        if options.json_output {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&to_json_configuration(&report))
+                    .expect("Failed to serialize JSON output")
+            );
+        } else {
+            print_configuration_report(&report);
+        }
+        std::process::exit(if report.succeeded() { 0 } else { 1 });
+    }
+
+    if options.probe_color {
+        let report = color_probe::probe_color_management(&mut event_queue, &mut app_data)
+            .unwrap_or_else(|err| {
+                eprintln!("{}", err.red());
+                std::process::exit(2);
+            });
+        if options.json_output {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&to_json_color_probe(&report))
+                    .expect("Failed to serialize JSON output")
+            );
+        } else {
+            print_color_probe_report(&report);
+        }
+        std::process::exit(if report.succeeded() { 0 } else { 1 });
+    }
+
+    if options.probe_capture {
+        let image_dir = options.capture_dir.as_deref().map(Path::new);
+        let report = capture_probe::probe_capture(&mut event_queue, &mut app_data, image_dir)
+            .unwrap_or_else(|err| {
+                eprintln!("{}", err.red());
+                std::process::exit(2);
+            });
+        if options.json_output {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&to_json_capture_probe(&report))
+                    .expect("Failed to serialize JSON output")
+            );
+        } else {
+            print_capture_probe_report(&report);
+        }
+        std::process::exit(if report.succeeded() { 0 } else { 1 });
+    }
+
     if options.json_output {
         if options.full_output {
             let json_payload = to_json_output(
@@ -1,8 +1,23 @@
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 use serde::Serialize;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::app::{AppData, GlobalInfo};
+use crate::capture_probe::{CaptureProbeReport, CaptureProbeResult};
+use crate::color_probe::{ColorProbeReport, ColorProbeResult};
+use crate::drm_device::DrmDevice;
+use crate::layout::ConfigurationReport;
+use crate::protocols::ext_image_copy_capture::CaptureConstraintsInfo;
+use crate::protocols::ext_workspace::WorkspaceInfo;
+use crate::protocols::kde_output_device::KdeOutputDeviceInfo;
+use crate::protocols::linux_dmabuf::DmabufInfo;
+use crate::protocols::plasma_window_management::PlasmaWindowInfo;
+use crate::protocols::wlr_export_dmabuf::ExportDmabufFrameInfo;
+use crate::protocols::wlr_foreign_toplevel::{WlrToplevelInfo, WlrToplevelManagerInfo};
+use crate::protocols::wlr_output_management::{ConfigurationResult, WlrOutputHeadInfo};
+use crate::protocols::wlr_screencopy::ScreencopyManagerInfo;
+use crate::protocols::wp_color_management::{Chromaticities, ImageDescriptionInfo};
+use crate::protocols::wp_tablet::TabletSeatInfo;
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -34,15 +49,44 @@ pub struct GlobalSummary {
     version: u32,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonConfigurationOutput {
+    generation_timestamp: u64,
+    #[serde(flatten)]
+    report: ConfigurationReport,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonCaptureProbeOutput {
+    generation_timestamp: u64,
+    #[serde(flatten)]
+    report: CaptureProbeReport,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JsonColorProbeOutput {
+    generation_timestamp: u64,
+    #[serde(flatten)]
+    report: ColorProbeReport,
+}
+
+// Milliseconds since the Unix epoch, stamped on every JSON document
+fn generation_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
 pub fn to_json_output(
     app_data: &AppData,
     sort_output: bool,
     protocol_filter: Option<&str>,
 ) -> JsonOutput {
-    let timestamp_ms = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0);
+    let timestamp_ms = generation_timestamp();
 
     let mut globals = Vec::new();
 
@@ -76,10 +120,7 @@ pub fn to_json_basic(
     sort_output: bool,
     protocol_filter: Option<&str>,
 ) -> JsonOutputBasic {
-    let timestamp_ms = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map(|d| d.as_millis() as u64)
-        .unwrap_or(0);
+    let timestamp_ms = generation_timestamp();
 
     let mut globals: Vec<GlobalSummary> = app_data
         .globals
@@ -100,6 +141,191 @@ pub fn to_json_basic(
     }
 }
 
+pub fn to_json_configuration(report: &ConfigurationReport) -> JsonConfigurationOutput {
+    JsonConfigurationOutput {
+        generation_timestamp: generation_timestamp(),
+        report: report.clone(),
+    }
+}
+
+// Layout runs report the outcome of a request and exit, like the probes, so the
+// report stands apart from the globals listed by print_all_info
+pub fn print_configuration_report(report: &ConfigurationReport) {
+    println!(
+        "{} {}",
+        "Output configuration".bold().blue(),
+        report.action.bold().blue()
+    );
+    for (index, result) in report.attempts.iter().enumerate() {
+        println!(
+            "        attempt {}: {}",
+            (index + 1).to_string().yellow(),
+            color_configuration_result(*result)
+        );
+    }
+    if let Some(confirmed) = report.confirmed {
+        println!("        confirmed: {}", confirmed.to_string().yellow());
+    }
+    for (index, result) in report.revert_attempts.iter().enumerate() {
+        println!(
+            "        revert attempt {}: {}",
+            (index + 1).to_string().yellow(),
+            color_configuration_result(*result)
+        );
+    }
+}
+
+fn color_configuration_result(result: ConfigurationResult) -> ColoredString {
+    match result {
+        ConfigurationResult::Succeeded => result.as_str().green(),
+        ConfigurationResult::Failed => result.as_str().red(),
+        ConfigurationResult::Cancelled => result.as_str().yellow(),
+    }
+}
+
+pub fn to_json_color_probe(report: &ColorProbeReport) -> JsonColorProbeOutput {
+    JsonColorProbeOutput {
+        generation_timestamp: generation_timestamp(),
+        report: report.clone(),
+    }
+}
+
+pub fn print_color_probe_report(report: &ColorProbeReport) {
+    let tf_width = report
+        .transfer_functions
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max(8);
+    let column_width = report
+        .primaries
+        .iter()
+        .map(String::len)
+        .max()
+        .unwrap_or(0)
+        .max(7);
+
+    for luminances in &report.luminances {
+        println!(
+            "{} {}",
+            "Color management probe, luminances:".bold().blue(),
+            luminances.bold().blue()
+        );
+        let header: Vec<String> = report
+            .primaries
+            .iter()
+            .map(|p| format!("{:<width$}", p, width = column_width))
+            .collect();
+        println!(
+            "        {:<width$} {}",
+            "",
+            header.join(" ").green(),
+            width = tf_width
+        );
+        for tf in &report.transfer_functions {
+            let cells: Vec<String> = report
+                .primaries
+                .iter()
+                .map(|primaries| {
+                    let status = report
+                        .results
+                        .iter()
+                        .find(|r| {
+                            &r.transfer_function == tf
+                                && &r.primaries == primaries
+                                && &r.luminances == luminances
+                        })
+                        .map_or("-", |r| r.status.as_str());
+                    let cell = format!("{:<width$}", status, width = column_width);
+                    match status {
+                        "ready" => cell.green().to_string(),
+                        "failed" => cell.red().to_string(),
+                        _ => cell.yellow().to_string(),
+                    }
+                })
+                .collect();
+            println!(
+                "        {} {}",
+                format!("{:<width$}", tf, width = tf_width).cyan(),
+                cells.join(" ")
+            );
+        }
+    }
+
+    let failures: Vec<&ColorProbeResult> = report
+        .results
+        .iter()
+        .filter(|r| r.failure.is_some())
+        .collect();
+    if !failures.is_empty() {
+        println!("{}", "Failures:".bold().blue());
+        for result in failures {
+            println!(
+                "        {} x {} ({}): {}",
+                result.transfer_function.cyan(),
+                result.primaries.green(),
+                result.luminances,
+                result.failure.as_deref().unwrap_or_default().red()
+            );
+        }
+    }
+}
+
+pub fn to_json_capture_probe(report: &CaptureProbeReport) -> JsonCaptureProbeOutput {
+    JsonCaptureProbeOutput {
+        generation_timestamp: generation_timestamp(),
+        report: report.clone(),
+    }
+}
+
+pub fn print_capture_probe_report(report: &CaptureProbeReport) {
+    println!("{}", "Capture probe".bold().blue());
+    for result in &report.results {
+        print_capture_probe_result(result);
+    }
+}
+
+fn print_capture_probe_result(result: &CaptureProbeResult) {
+    let status = match result.status.as_str() {
+        "ready" => result.status.green(),
+        "failed" => result.status.red(),
+        _ => result.status.yellow(),
+    };
+    println!(
+        "        output {}: {} via {}",
+        result.output.yellow(),
+        status,
+        result.protocol.cyan()
+    );
+    if let Some(failure) = &result.failure {
+        println!("                failure: {}", failure.red());
+    }
+    if let Some(buffer) = &result.buffer {
+        println!(
+            "                buffer: {} {}, stride {}",
+            buffer.fourcc.green(),
+            format!("{}x{}", buffer.width, buffer.height).yellow(),
+            buffer.stride.to_string().yellow()
+        );
+    }
+    if !result.flags.is_empty() {
+        println!("                flags: {}", result.flags.join(" ").cyan());
+    }
+    if let Some(transform) = &result.transform {
+        println!("                transform: {}", transform.cyan());
+    }
+    if let Some(time) = &result.presentation_time {
+        println!(
+            "                presentation time: {}",
+            format!("{}.{:09}", time.seconds, time.nanoseconds).yellow()
+        );
+    }
+    if let Some(image) = &result.image {
+        println!("                image: {}", image.green());
+    }
+}
+
 pub fn print_all_info(app_data: &AppData, sort_output: bool, protocol_filter: Option<&str>) {
     if let Some(protocol) = protocol_filter {
         if !app_data.globals.iter().any(|g| g.interface == protocol) {
@@ -165,12 +391,44 @@ pub fn print_all_info(app_data: &AppData, sort_output: bool, protocol_filter: Op
                         delay.to_string().yellow()
                     );
                 }
+                if let Some(keymap) = &seat.keymap {
+                    println!(
+                        "        keymap: {} ({} bytes)",
+                        keymap.format.cyan(),
+                        keymap.size.to_string().yellow()
+                    );
+                    if !keymap.summary.layouts.is_empty() {
+                        let layouts: Vec<String> = keymap
+                            .summary
+                            .layouts
+                            .iter()
+                            .zip(&keymap.summary.variants)
+                            .map(|(layout, variant)| {
+                                if variant.is_empty() {
+                                    layout.clone()
+                                } else {
+                                    format!("{}({})", layout, variant)
+                                }
+                            })
+                            .collect();
+                        println!("                layouts: {}", layouts.join(" ").green());
+                    }
+                    if !keymap.summary.options.is_empty() {
+                        println!(
+                            "                options: {}",
+                            keymap.summary.options.join(" ").cyan()
+                        );
+                    }
+                }
             }
         }
 
         if global.interface == "wl_output" {
             if let Some(output) = app_data.outputs.iter().find(|o| o.name == global.name) {
                 println!("        name: {}", output.output_name.yellow());
+                if let Some(priority) = app_data.kde_output_priority(&output.output_name) {
+                    println!("        priority: {}", priority.to_string().yellow());
+                }
                 if !output.description.is_empty() {
                     println!("        description: {}", output.description.cyan());
                 }
@@ -203,6 +461,233 @@ pub fn print_all_info(app_data: &AppData, sort_output: bool, protocol_filter: Op
                         "        mode:\n                width: {} px, height: {} px, refresh: {:.3} Hz,\n                flags: {}", mode.width.to_string().yellow(), mode.height.to_string().yellow(), (mode.refresh as f32 / 1000.0).to_string().yellow(), mode.flags.join(" ").cyan()
                     );
                 }
+
+                if let Some(head) = app_data.wlr_output_head(&output.output_name) {
+                    print_wlr_output_head(head);
+                }
+
+                if let Some(description) = &output.image_description {
+                    print_image_description(description);
+                }
+
+                if let Some(constraints) = &output.capture_constraints {
+                    print_capture_constraints(constraints, "        ");
+                }
+
+                if let Some(frame) = &output.export_dmabuf_frame {
+                    print_export_dmabuf_frame(frame);
+                }
+            }
+        }
+
+        if global.interface == "zwlr_output_manager_v1" {
+            if let Some(manager) = app_data
+                .wlr_output_managers
+                .iter()
+                .find(|m| m.name == global.name)
+            {
+                for head in app_data.wlr_output_heads_without_output(manager) {
+                    print_wlr_output_head(head);
+                }
+            } else {
+                println!(
+                    "{}",
+                    "        [Warning] wlr output manager info not found!".red()
+                );
+            }
+        }
+
+        if global.interface == "kde_output_device_v2" {
+            if let Some(device) = app_data
+                .kde_output_devices
+                .iter()
+                .find(|d| d.name == global.name)
+            {
+                print_kde_output_device(device);
+            } else {
+                println!(
+                    "{}",
+                    "        [Warning] KDE output device info not found!".red()
+                );
+            }
+        }
+
+        if global.interface == "kde_output_order_v1" {
+            if let Some(order) = app_data
+                .kde_output_orders
+                .iter()
+                .find(|o| o.name == global.name)
+            {
+                for (priority, output_name) in order.outputs.iter().enumerate() {
+                    println!(
+                        "        {}: {}",
+                        priority.to_string().yellow(),
+                        output_name.green()
+                    );
+                }
+            }
+        }
+
+        if global.interface == "ext_workspace_manager_v1" {
+            if let Some(manager) = app_data
+                .workspace_managers
+                .iter()
+                .find(|m| m.name == global.name)
+            {
+                for group in &manager.groups {
+                    println!("        {}", "workspace group".cyan());
+                    if !group.capabilities.is_empty() {
+                        println!(
+                            "                capabilities: {}",
+                            group.capabilities.join(" ").cyan()
+                        );
+                    }
+                    if !group.outputs.is_empty() {
+                        println!(
+                            "                outputs: {}",
+                            group.outputs.join(" ").yellow()
+                        );
+                    }
+                    for workspace in &group.workspaces {
+                        print_workspace(workspace, "                ");
+                    }
+                }
+                for workspace in &manager.ungrouped_workspaces {
+                    print_workspace(workspace, "        ");
+                }
+            }
+        }
+
+        if global.interface == "ext_foreign_toplevel_list_v1" {
+            if let Some(list) = app_data
+                .foreign_toplevel_lists
+                .iter()
+                .find(|l| l.name == global.name)
+            {
+                for toplevel in &list.toplevels {
+                    println!("        {}", "toplevel".cyan());
+                    println!("                title: '{}'", toplevel.title.green());
+                    println!("                app_id: '{}'", toplevel.app_id.green());
+                    println!(
+                        "                identifier: {}",
+                        toplevel.identifier.dimmed()
+                    );
+                    if let Some(constraints) = &toplevel.capture_constraints {
+                        print_capture_constraints(constraints, "                ");
+                    }
+                }
+            }
+        }
+
+        if global.interface == "zwlr_foreign_toplevel_manager_v1" {
+            if let Some(manager) = app_data
+                .wlr_toplevel_managers
+                .iter()
+                .find(|m| m.name == global.name)
+            {
+                print_wlr_toplevels(app_data, manager);
+            }
+        }
+
+        if global.interface == "org_kde_plasma_window_management" {
+            if let Some(manager) = app_data
+                .plasma_window_managers
+                .iter()
+                .find(|m| m.name == global.name)
+            {
+                println!(
+                    "        show desktop: {}",
+                    if manager.show_desktop {
+                        "enabled"
+                    } else {
+                        "disabled"
+                    }
+                    .cyan()
+                );
+                for window in &manager.windows {
+                    print_plasma_window(app_data, window);
+                }
+            }
+        }
+
+        if global.interface == "org_kde_plasma_virtual_desktop_management" {
+            if let Some(manager) = app_data
+                .plasma_virtual_desktop_managers
+                .iter()
+                .find(|m| m.name == global.name)
+            {
+                if let Some(rows) = manager.rows {
+                    println!("        rows: {}", rows.to_string().yellow());
+                }
+                for desktop in &manager.desktops {
+                    println!(
+                        "        {}: '{}' id: {}{}",
+                        desktop.position.to_string().yellow(),
+                        desktop.name.green(),
+                        desktop.id.dimmed(),
+                        if desktop.active {
+                            format!(" {}", "active".cyan())
+                        } else {
+                            String::new()
+                        }
+                    );
+                }
+            }
+        }
+
+        if global.interface == "zwp_tablet_manager_v2" {
+            if let Some(manager) = app_data
+                .tablet_managers
+                .iter()
+                .find(|m| m.name == global.name)
+            {
+                for seat in &manager.seats {
+                    print_tablet_seat(seat);
+                }
+            }
+        }
+
+        if global.interface == "wp_color_manager_v1" {
+            if let Some(manager) = app_data
+                .color_managers
+                .iter()
+                .find(|m| m.name == global.name)
+            {
+                println!("        intents: {}", manager.intents.join(" ").cyan());
+                println!("        features: {}", manager.features.join(" ").cyan());
+                println!(
+                    "        transfer functions: {}",
+                    manager.transfer_functions.join(" ").cyan()
+                );
+                println!("        primaries: {}", manager.primaries.join(" ").cyan());
+            }
+        }
+
+        if global.interface == "wp_color_representation_manager_v1" {
+            if let Some(info) = app_data
+                .color_representation_info
+                .iter()
+                .find(|r| r.name == global.name)
+            {
+                println!("        alpha modes: {}", info.alpha_modes.join(" ").cyan());
+                println!("        coefficients and ranges:");
+                for pair in &info.coefficients_and_ranges {
+                    println!(
+                        "                {} / {}",
+                        pair.coefficients.green(),
+                        pair.range.cyan()
+                    );
+                }
+            }
+        }
+
+        if global.interface == "zwlr_screencopy_manager_v1" {
+            if let Some(manager) = app_data
+                .screencopy_managers
+                .iter()
+                .find(|m| m.name == global.name)
+            {
+                print_screencopy_frames(manager);
             }
         }
 
@@ -218,6 +703,47 @@ pub fn print_all_info(app_data: &AppData, sort_output: bool, protocol_filter: Op
             }
         }
 
+        if global.interface == "zwp_linux_dmabuf_v1" {
+            if let Some(dmabuf) = app_data.dmabuf_info.iter().find(|d| d.name == global.name) {
+                print_dmabuf_info(dmabuf);
+            } else {
+                println!("{}", "        [Warning] Linux dmabuf info not found!".red());
+            }
+        }
+
+        if global.interface == "wl_drm" {
+            if let Some(drm) = app_data.wl_drm_info.iter().find(|d| d.name == global.name) {
+                if let Some(path) = &drm.device_path {
+                    println!("        device: {}", path.green());
+                } else {
+                    println!("        device: {}", "<unknown>".red());
+                }
+                if let Some(device) = &drm.device {
+                    println!("        device number: {}", format_drm_device(device));
+                }
+                if !drm.capabilities.is_empty() {
+                    println!(
+                        "        capabilities: {}",
+                        drm.capabilities.join(" ").cyan()
+                    );
+                }
+                if drm.authenticated {
+                    println!("        authenticated: {}", "true".yellow());
+                } else {
+                    println!("        authenticated: {}", "not attempted".dimmed());
+                }
+                for format in &drm.formats {
+                    println!(
+                        "        format: {} ({})",
+                        format.fourcc.green(),
+                        format.format.to_string().yellow()
+                    );
+                }
+            } else {
+                println!("{}", "        [Warning] wl_drm info not found!".red());
+            }
+        }
+
         if global.interface == "wp_drm_lease_device_v1" {
             if let Some(device) = app_data
                 .drm_lease_devices
@@ -229,15 +755,23 @@ pub fn print_all_info(app_data: &AppData, sort_output: bool, protocol_filter: Op
                 } else {
                     println!("        path: {}", "<unknown>".red());
                 }
+                if let Some(drm_device) = &device.device {
+                    println!("        device: {}", format_drm_device(drm_device));
+                }
 
                 if !device.connectors.is_empty() {
                     println!("        connectors:");
                     for connector in &device.connectors {
                         println!(
-                            "                name: {}, description: {}, connector_id: {}",
+                            "                name: {}, description: {}, connector_id: {}{}",
                             connector.name.green(),
                             connector.description.cyan(),
-                            connector.connector_id.to_string().yellow()
+                            connector.connector_id.to_string().yellow(),
+                            if connector.withdrawn {
+                                format!(" {}", "(withdrawn)".red())
+                            } else {
+                                String::new()
+                            }
                         );
                     }
                 }
@@ -360,12 +894,642 @@ pub fn print_basic_info(app_data: &AppData, sort_output: bool, protocol_filter:
     }
 }
 
+fn print_wlr_output_head(head: &WlrOutputHeadInfo) {
+    println!("        {}", "head".cyan());
+    println!("                name: {}", head.name.yellow());
+    if !head.description.is_empty() {
+        println!("                description: {}", head.description.cyan());
+    }
+    println!(
+        "                make: '{}', model: '{}', serial: '{}',",
+        head.make.green(),
+        head.model.green(),
+        head.serial_number.green()
+    );
+    println!(
+        "                physical_width: {} mm, physical_height: {} mm,",
+        head.physical_width.to_string().yellow(),
+        head.physical_height.to_string().yellow()
+    );
+    println!(
+        "                enabled: {}, x: {}, y: {}, scale: {}, transform: {},",
+        head.enabled.to_string().yellow(),
+        head.x.to_string().yellow(),
+        head.y.to_string().yellow(),
+        head.scale.to_string().yellow(),
+        head.transform.cyan()
+    );
+    if let Some(adaptive_sync) = &head.adaptive_sync {
+        println!("                adaptive_sync: {}", adaptive_sync.cyan());
+    }
+
+    for mode in &head.modes {
+        let mut flags = Vec::new();
+        if mode.preferred {
+            flags.push("preferred");
+        }
+        if mode.current {
+            flags.push("current");
+        }
+        println!(
+            "                mode:\n                        width: {} px, height: {} px, refresh: {:.3} Hz,\n                        flags: {}", mode.width.to_string().yellow(), mode.height.to_string().yellow(), (mode.refresh as f32 / 1000.0).to_string().yellow(), flags.join(" ").cyan()
+        );
+    }
+}
+
+fn print_kde_output_device(device: &KdeOutputDeviceInfo) {
+    println!("        name: {}", device.output_name.yellow());
+    if !device.uuid.is_empty() {
+        println!("        uuid: {}", device.uuid.dimmed());
+    }
+    println!(
+        "        make: '{}', model: '{}', serial: '{}', eisa_id: '{}',",
+        device.make.green(),
+        device.model.green(),
+        device.serial_number.green(),
+        device.eisa_id.green()
+    );
+    println!(
+        "        enabled: {}, x: {}, y: {}, scale: {},",
+        device.enabled.to_string().yellow(),
+        device.x.to_string().yellow(),
+        device.y.to_string().yellow(),
+        device.scale.to_string().yellow()
+    );
+    println!(
+        "        physical_width: {} mm, physical_height: {} mm,",
+        device.physical_width.to_string().yellow(),
+        device.physical_height.to_string().yellow()
+    );
+    println!(
+        "        subpixel_orientation: {}, output_transform: {},",
+        device.subpixel_orientation.cyan(),
+        device.output_transform.cyan()
+    );
+    if !device.capabilities.is_empty() {
+        println!(
+            "        capabilities: {}",
+            device.capabilities.join(" ").cyan()
+        );
+    }
+    if let Some(hdr) = device.high_dynamic_range {
+        println!("        high_dynamic_range: {}", hdr.to_string().yellow());
+    }
+    if let Some(brightness) = device.sdr_brightness {
+        println!(
+            "        sdr_brightness: {}",
+            brightness.to_string().yellow()
+        );
+    }
+    if let Some(wcg) = device.wide_color_gamut {
+        println!("        wide_color_gamut: {}", wcg.to_string().yellow());
+    }
+    if let Some(policy) = &device.vrr_policy {
+        println!("        vrr_policy: {}", policy.cyan());
+    }
+    if let Some(overscan) = device.overscan {
+        println!("        overscan: {}", overscan.to_string().yellow());
+    }
+    if let Some(range) = &device.rgb_range {
+        println!("        rgb_range: {}", range.cyan());
+    }
+    if let Some(policy) = &device.auto_rotate_policy {
+        println!("        auto_rotate_policy: {}", policy.cyan());
+    }
+    if let Some(source) = &device.color_profile_source {
+        println!("        color_profile_source: {}", source.cyan());
+    }
+    if let Some(path) = &device.icc_profile_path {
+        if !path.is_empty() {
+            println!("        icc_profile_path: {}", path.green());
+        }
+    }
+    if let Some(brightness) = device.brightness {
+        println!("        brightness: {}", brightness.to_string().yellow());
+    }
+    if let Some(edid) = device.edid_summary() {
+        println!(
+            "        edid: vendor: {}, product: {}, serial: {}",
+            edid.vendor.green(),
+            format!("0x{:04x}", edid.product_code).yellow(),
+            edid.serial.to_string().yellow()
+        );
+        if let Some(name) = &edid.monitor_name {
+            println!("                monitor name: '{}'", name.green());
+        }
+        if let Some(serial) = &edid.serial_string {
+            println!("                serial string: '{}'", serial.green());
+        }
+    } else if !device.edid.is_empty() {
+        println!(
+            "        edid: {} bytes, {}",
+            device.edid.len().to_string().yellow(),
+            "unparseable".red()
+        );
+    }
+
+    for mode in &device.modes {
+        let mut flags = Vec::new();
+        if mode.preferred {
+            flags.push("preferred");
+        }
+        if mode.current {
+            flags.push("current");
+        }
+        println!(
+            "        mode:\n                width: {} px, height: {} px, refresh: {:.3} Hz,\n                flags: {}", mode.width.to_string().yellow(), mode.height.to_string().yellow(), (mode.refresh as f32 / 1000.0).to_string().yellow(), flags.join(" ").cyan()
+        );
+    }
+}
+
+fn print_workspace(workspace: &WorkspaceInfo, indent: &str) {
+    println!("{}{}", indent, "workspace".cyan());
+    println!(
+        "{}        id: '{}', name: '{}'",
+        indent,
+        workspace.id.as_deref().unwrap_or_default().green(),
+        workspace.name.green()
+    );
+    if !workspace.coordinates.is_empty() {
+        let coordinates: Vec<String> = workspace.coordinates.iter().map(u32::to_string).collect();
+        println!(
+            "{}        coordinates: {}",
+            indent,
+            coordinates.join(",").yellow()
+        );
+    }
+    if !workspace.state.is_empty() {
+        println!(
+            "{}        state: {}",
+            indent,
+            workspace.state.join(" ").cyan()
+        );
+    }
+    if !workspace.capabilities.is_empty() {
+        println!(
+            "{}        capabilities: {}",
+            indent,
+            workspace.capabilities.join(" ").cyan()
+        );
+    }
+}
+
+fn print_wlr_toplevels(app_data: &AppData, manager: &WlrToplevelManagerInfo) {
+    // Group windows by output, in output order, then windows on no known output
+    let mut groups: Vec<(String, Vec<&WlrToplevelInfo>)> = app_data
+        .outputs
+        .iter()
+        .map(|output| {
+            let toplevels = manager
+                .toplevels
+                .iter()
+                .filter(|t| t.outputs.contains(&output.output_name))
+                .collect();
+            (output.output_name.clone(), toplevels)
+        })
+        .collect();
+    groups.push((
+        "<none>".to_string(),
+        manager
+            .toplevels
+            .iter()
+            .filter(|t| t.outputs.is_empty())
+            .collect(),
+    ));
+
+    for (output_name, toplevels) in groups {
+        if toplevels.is_empty() {
+            continue;
+        }
+        println!("        output: {}", output_name.yellow());
+        println!(
+            "                {:<6} {:<24} {:<32} {:<28} parent",
+            "id", "app_id", "title", "state"
+        );
+        for toplevel in toplevels {
+            println!(
+                "                {:<6} {:<24} {:<32} {:<28} {}",
+                toplevel.id.to_string().dimmed(),
+                toplevel.app_id.green(),
+                toplevel.title.green(),
+                toplevel.state.join(" ").cyan(),
+                toplevel
+                    .parent
+                    .map_or_else(|| "-".to_string(), |p| p.to_string())
+                    .dimmed()
+            );
+        }
+    }
+}
+
+fn print_screencopy_frames(manager: &ScreencopyManagerInfo) {
+    if manager.frames.is_empty() {
+        return;
+    }
+    println!(
+        "        {:<16} {:<6} {:<8} {:<6} {:<12} {:<8} dmabuf",
+        "output", "cursor", "state", "shm", "size", "stride"
+    );
+    for frame in &manager.frames {
+        let state = match frame.state.as_str() {
+            "done" => frame.state.green(),
+            "failed" => frame.state.red(),
+            _ => frame.state.yellow(),
+        };
+        let (fourcc, size, stride) = match &frame.buffer {
+            Some(buffer) => (
+                buffer.fourcc.clone(),
+                format!("{}x{}", buffer.width, buffer.height),
+                buffer.stride.to_string(),
+            ),
+            None => ("-".to_string(), "-".to_string(), "-".to_string()),
+        };
+        let dmabuf = frame.linux_dmabuf.as_ref().map_or_else(
+            || "-".to_string(),
+            |dmabuf| format!("{} {}x{}", dmabuf.fourcc, dmabuf.width, dmabuf.height),
+        );
+        println!(
+            "        {:<16} {:<6} {:<8} {:<6} {:<12} {:<8} {}",
+            frame.output.yellow(),
+            if frame.overlay_cursor { "yes" } else { "no" }.cyan(),
+            state,
+            fourcc.green(),
+            size.yellow(),
+            stride.yellow(),
+            dmabuf.green()
+        );
+    }
+}
+
+fn print_plasma_window(app_data: &AppData, window: &PlasmaWindowInfo) {
+    println!("        window '{}'", window.title.green());
+    println!("                uuid: {}", window.uuid.dimmed());
+    println!("                app_id: {}", window.app_id.green());
+    if let Some(pid) = window.pid {
+        println!("                pid: {}", pid.to_string().yellow());
+    }
+    if let Some(geometry) = &window.geometry {
+        println!(
+            "                geometry: {} at {}",
+            format!("{}x{}", geometry.width, geometry.height).yellow(),
+            format!("{},{}", geometry.x, geometry.y).yellow()
+        );
+    }
+    if !window.virtual_desktops.is_empty() {
+        let desktops: Vec<String> = window
+            .virtual_desktops
+            .iter()
+            .map(|id| {
+                app_data
+                    .plasma_virtual_desktop_name(id)
+                    .map_or_else(|| id.clone(), |name| format!("'{}'", name))
+            })
+            .collect();
+        println!(
+            "                virtual desktops: {}",
+            desktops.join(" ").green()
+        );
+    }
+    if !window.state.is_empty() {
+        println!("                state: {}", window.state.join(" ").cyan());
+    }
+    if let Some(parent) = &window.parent {
+        println!("                parent: {}", parent.dimmed());
+    }
+}
+
+fn print_tablet_seat(seat: &TabletSeatInfo) {
+    println!("        seat: {}", seat.seat.green());
+    for tablet in &seat.tablets {
+        println!("                tablet '{}'", tablet.name.green());
+        if let (Some(vid), Some(pid)) = (tablet.vid, tablet.pid) {
+            println!(
+                "                        vid:pid: {}",
+                format!("{:04x}:{:04x}", vid, pid).yellow()
+            );
+        }
+        if let Some(bustype) = &tablet.bustype {
+            println!("                        bustype: {}", bustype.cyan());
+        }
+        for path in &tablet.paths {
+            println!("                        path: {}", path.green());
+        }
+    }
+    for tool in &seat.tools {
+        println!("                tool {}", tool.tool_type.cyan());
+        if let Some(serial) = &tool.hardware_serial {
+            println!(
+                "                        hardware serial: {}",
+                serial.yellow()
+            );
+        }
+        if let Some(id) = &tool.hardware_id_wacom {
+            println!(
+                "                        hardware id (wacom): {}",
+                id.yellow()
+            );
+        }
+        if !tool.capabilities.is_empty() {
+            println!(
+                "                        capabilities: {}",
+                tool.capabilities.join(" ").cyan()
+            );
+        }
+    }
+    for pad in &seat.pads {
+        println!(
+            "                pad, buttons: {}",
+            pad.buttons.to_string().yellow()
+        );
+        for path in &pad.paths {
+            println!("                        path: {}", path.green());
+        }
+        for group in &pad.groups {
+            let buttons: Vec<String> = group.buttons.iter().map(u32::to_string).collect();
+            println!(
+                "                        group buttons: [{}], rings: {}, strips: {}, dials: {}, modes: {}",
+                buttons.join(" ").yellow(),
+                group.rings.to_string().yellow(),
+                group.strips.to_string().yellow(),
+                group.dials.to_string().yellow(),
+                group.modes.to_string().yellow()
+            );
+        }
+    }
+}
+
+fn print_image_description(description: &ImageDescriptionInfo) {
+    let state = match description.state.as_str() {
+        "ready" => description.state.green(),
+        "failed" => description.state.red(),
+        _ => description.state.yellow(),
+    };
+    match description.identity {
+        Some(identity) => println!(
+            "        image description: {} (identity {})",
+            state,
+            identity.to_string().dimmed()
+        ),
+        None => println!("        image description: {}", state),
+    }
+    if let Some(failure) = &description.failure {
+        println!("                failure: {}", failure.red());
+    }
+    if let Some(size) = description.icc_size {
+        println!(
+            "                icc file: {} bytes",
+            size.to_string().yellow()
+        );
+        if let Some(profile) = &description.icc_description {
+            println!("                        description: '{}'", profile.green());
+        }
+        if let Some(path) = &description.icc_path {
+            println!("                        exported to: {}", path.green());
+        }
+    }
+    if let Some(primaries) = &description.primaries_named {
+        println!("                primaries: {}", primaries.cyan());
+    }
+    if let Some(primaries) = &description.primaries {
+        println!(
+            "                primaries: {}",
+            format_chromaticities(primaries)
+        );
+    }
+    if let Some(tf) = &description.tf_named {
+        println!("                transfer function: {}", tf.cyan());
+    }
+    if let Some(exponent) = description.tf_power {
+        println!(
+            "                transfer function: power {}",
+            format!("{:.4}", exponent).yellow()
+        );
+    }
+    if let Some(luminances) = &description.luminances {
+        println!(
+            "                luminances: min {}, max {}, reference {} cd/m2",
+            format!("{:.4}", luminances.min).yellow(),
+            luminances.max.to_string().yellow(),
+            luminances
+                .reference
+                .unwrap_or_default()
+                .to_string()
+                .yellow()
+        );
+    }
+    if let Some(primaries) = &description.target_primaries {
+        println!(
+            "                target primaries: {}",
+            format_chromaticities(primaries)
+        );
+    }
+    if let Some(luminance) = &description.target_luminance {
+        println!(
+            "                target luminance: min {}, max {} cd/m2",
+            format!("{:.4}", luminance.min).yellow(),
+            luminance.max.to_string().yellow()
+        );
+    }
+    if let Some(max_cll) = description.target_max_cll {
+        println!(
+            "                target max CLL: {} cd/m2",
+            max_cll.to_string().yellow()
+        );
+    }
+    if let Some(max_fall) = description.target_max_fall {
+        println!(
+            "                target max FALL: {} cd/m2",
+            max_fall.to_string().yellow()
+        );
+    }
+}
+
+fn print_capture_constraints(constraints: &CaptureConstraintsInfo, indent: &str) {
+    let state = match constraints.state.as_str() {
+        "done" => constraints.state.green(),
+        "stopped" => constraints.state.red(),
+        _ => constraints.state.yellow(),
+    };
+    println!("{}capture constraints: {}", indent, state);
+    if let Some(size) = &constraints.buffer_size {
+        println!(
+            "{}        buffer size: {}x{}",
+            indent,
+            size.width.to_string().yellow(),
+            size.height.to_string().yellow()
+        );
+    }
+    if !constraints.shm_formats.is_empty() {
+        let formats: Vec<String> = constraints
+            .shm_formats
+            .iter()
+            .map(|f| f.fourcc.green().to_string())
+            .collect();
+        println!("{}        shm formats: {}", indent, formats.join(" "));
+    }
+    if let Some(device) = &constraints.dmabuf_device {
+        println!(
+            "{}        dmabuf device: {}",
+            indent,
+            format_drm_device(device)
+        );
+    }
+    for format in &constraints.dmabuf_formats {
+        println!(
+            "{}        dmabuf format: {} ({}), modifiers: {}",
+            indent,
+            format.fourcc.green(),
+            format.format.to_string().yellow(),
+            format.modifiers.join(" ").cyan()
+        );
+    }
+}
+
+fn print_export_dmabuf_frame(frame: &ExportDmabufFrameInfo) {
+    let state = match frame.state.as_str() {
+        "ready" => frame.state.green(),
+        "cancelled" => frame.state.red(),
+        _ => frame.state.yellow(),
+    };
+    match &frame.cancel_reason {
+        Some(reason) => println!("        export dmabuf frame: {} ({})", state, reason.red()),
+        None => println!("        export dmabuf frame: {}", state),
+    }
+    if frame.num_objects == 0 {
+        return;
+    }
+    println!(
+        "                format: {} ({}), modifier: {}",
+        frame.fourcc.green(),
+        frame.format.to_string().yellow(),
+        frame.modifier.cyan()
+    );
+    println!(
+        "                size: {}, offset: {}",
+        format!("{}x{}", frame.width, frame.height).yellow(),
+        format!("{},{}", frame.offset_x, frame.offset_y).yellow()
+    );
+    let mut flags = frame.buffer_flags.clone();
+    if frame.transient {
+        flags.push("transient".to_string());
+    }
+    if !flags.is_empty() {
+        println!("                flags: {}", flags.join(" ").cyan());
+    }
+    println!(
+        "                objects: {}",
+        frame.num_objects.to_string().yellow()
+    );
+    for object in &frame.objects {
+        println!(
+            "                        object {}: plane {}, offset {}, stride {}, size {}",
+            object.index.to_string().dimmed(),
+            object.plane_index.to_string().yellow(),
+            object.offset.to_string().yellow(),
+            object.stride.to_string().yellow(),
+            object.size.to_string().yellow()
+        );
+    }
+    if let Some(time) = &frame.presentation_time {
+        println!(
+            "                presentation time: {}",
+            format!("{}.{:09}", time.seconds, time.nanoseconds).yellow()
+        );
+    }
+}
+
+fn format_chromaticities(c: &Chromaticities) -> String {
+    let xy = |p: &[f64; 2]| format!("({:.4}, {:.4})", p[0], p[1]).yellow().to_string();
+    format!(
+        "r {} g {} b {} w {}",
+        xy(&c.red),
+        xy(&c.green),
+        xy(&c.blue),
+        xy(&c.white)
+    )
+}
+
+fn print_dmabuf_info(dmabuf: &DmabufInfo) {
+    if let Some(device) = &dmabuf.main_device {
+        println!("        main device: {}", format_drm_device(device));
+    }
+
+    for tranche in &dmabuf.tranches {
+        println!("        tranche:");
+        if let Some(device) = &tranche.target_device {
+            println!(
+                "                target device: {}",
+                format_drm_device(device)
+            );
+        }
+        if tranche.scanout {
+            println!("                flags: {}", "scanout".cyan());
+        }
+        for format in &tranche.formats {
+            if format.modifiers.is_empty() {
+                println!(
+                    "                format: {} ({})",
+                    format.fourcc.green(),
+                    format.format.to_string().yellow()
+                );
+            } else {
+                println!(
+                    "                format: {} ({}), modifiers: {}",
+                    format.fourcc.green(),
+                    format.format.to_string().yellow(),
+                    format.modifiers.join(" ").cyan()
+                );
+            }
+        }
+    }
+}
+
+fn format_drm_device(device: &DrmDevice) -> String {
+    let mut details = Vec::new();
+    if let Some(node) = &device.node {
+        details.push(node.green().to_string());
+    }
+    if let Some(driver) = &device.driver {
+        details.push(format!("driver: {}", driver.cyan()));
+    }
+    if let (Some(vendor), Some(id)) = (device.vendor_id, device.device_id) {
+        details.push(format!(
+            "pci: {}",
+            format!("{:04x}:{:04x}", vendor, id).yellow()
+        ));
+    }
+    if device.boot_vga == Some(true) {
+        details.push("boot_vga".cyan().to_string());
+    }
+
+    let dev = format!("{}:{}", device.major, device.minor).yellow();
+    if details.is_empty() {
+        dev.to_string()
+    } else {
+        format!("{} ({})", dev, details.join(", "))
+    }
+}
+
 fn protocol_has_details(protocol: &str) -> bool {
     matches!(
         protocol,
         "wl_seat"
             | "wl_output"
+            | "zwlr_output_manager_v1"
+            | "kde_output_device_v2"
+            | "kde_output_order_v1"
+            | "ext_workspace_manager_v1"
+            | "ext_foreign_toplevel_list_v1"
+            | "zwlr_foreign_toplevel_manager_v1"
+            | "org_kde_plasma_window_management"
+            | "org_kde_plasma_virtual_desktop_management"
+            | "zwp_tablet_manager_v2"
+            | "wp_color_manager_v1"
+            | "wp_color_representation_manager_v1"
+            | "zwlr_screencopy_manager_v1"
             | "wl_shm"
+            | "zwp_linux_dmabuf_v1"
+            | "wl_drm"
             | "wp_drm_lease_device_v1"
             | "wp_presentation"
             | "treeland_output_manager_v1"
@@ -384,16 +1548,91 @@ fn get_protocol_info_json(
             .iter()
             .find(|s| s.name == name)
             .map(|s| serde_json::to_value(vec![s]).unwrap()),
-        "wl_output" => app_data
-            .outputs
+        "wl_output" => app_data.outputs.iter().find(|o| o.name == name).map(|o| {
+            let mut output = serde_json::to_value(o).unwrap();
+            if let Some(head) = app_data.wlr_output_head(&o.output_name) {
+                output["wlrHead"] = serde_json::to_value(head).unwrap();
+            }
+            serde_json::Value::Array(vec![output])
+        }),
+        "zwlr_output_manager_v1" => app_data
+            .wlr_output_managers
+            .iter()
+            .find(|m| m.name == name)
+            .map(|m| {
+                let heads = app_data.wlr_output_heads_without_output(m);
+                serde_json::json!([{ "heads": heads }])
+            }),
+        "kde_output_device_v2" => app_data
+            .kde_output_devices
+            .iter()
+            .find(|d| d.name == name)
+            .map(|d| serde_json::to_value(vec![d]).unwrap()),
+        "kde_output_order_v1" => app_data
+            .kde_output_orders
             .iter()
             .find(|o| o.name == name)
             .map(|o| serde_json::to_value(vec![o]).unwrap()),
+        "ext_workspace_manager_v1" => app_data
+            .workspace_managers
+            .iter()
+            .find(|m| m.name == name)
+            .map(|m| serde_json::to_value(vec![m]).unwrap()),
+        "ext_foreign_toplevel_list_v1" => app_data
+            .foreign_toplevel_lists
+            .iter()
+            .find(|l| l.name == name)
+            .map(|l| serde_json::to_value(vec![l]).unwrap()),
+        "zwlr_foreign_toplevel_manager_v1" => app_data
+            .wlr_toplevel_managers
+            .iter()
+            .find(|m| m.name == name)
+            .map(|m| serde_json::to_value(vec![m]).unwrap()),
+        "org_kde_plasma_window_management" => app_data
+            .plasma_window_managers
+            .iter()
+            .find(|m| m.name == name)
+            .map(|m| serde_json::to_value(vec![m]).unwrap()),
+        "org_kde_plasma_virtual_desktop_management" => app_data
+            .plasma_virtual_desktop_managers
+            .iter()
+            .find(|m| m.name == name)
+            .map(|m| serde_json::to_value(vec![m]).unwrap()),
+        "zwp_tablet_manager_v2" => app_data
+            .tablet_managers
+            .iter()
+            .find(|m| m.name == name)
+            .map(|m| serde_json::to_value(vec![m]).unwrap()),
+        "wp_color_manager_v1" => app_data
+            .color_managers
+            .iter()
+            .find(|m| m.name == name)
+            .map(|m| serde_json::to_value(vec![m]).unwrap()),
+        "wp_color_representation_manager_v1" => app_data
+            .color_representation_info
+            .iter()
+            .find(|r| r.name == name)
+            .map(|r| serde_json::to_value(vec![r]).unwrap()),
+        "zwlr_screencopy_manager_v1" => app_data
+            .screencopy_managers
+            .iter()
+            .find(|m| m.name == name)
+            .map(|m| serde_json::to_value(vec![m]).unwrap()),
         "wl_shm" => app_data
             .shm_info
             .iter()
             .find(|s| s.name == name)
             .map(|s| serde_json::to_value(vec![s]).unwrap()),
+        "zwp_linux_dmabuf_v1" => app_data
+            .dmabuf_info
+            .iter()
+            .find(|d| d.name == name)
+            .map(|d| serde_json::to_value(vec![d]).unwrap()),
+        "wl_drm" => app_data
+            .wl_drm_info
+            .iter()
+            .find(|d| d.name == name)
+            .map(|d| serde_json::to_value(vec![d]).unwrap()),
         "wp_drm_lease_device_v1" => app_data
             .drm_lease_devices
             .iter()
@@ -0,0 +1,150 @@
+use serde::Serialize;
+use wayland_client::{event_created_child, Connection, Dispatch, QueueHandle};
+use wayland_protocols::ext::foreign_toplevel_list::v1::client::{
+    ext_foreign_toplevel_handle_v1::{self, ExtForeignToplevelHandleV1},
+    ext_foreign_toplevel_list_v1::{self, ExtForeignToplevelListV1},
+};
+
+use crate::app::{AppData, UserData};
+use crate::protocols::ext_image_copy_capture::CaptureConstraintsInfo;
+
+// Foreign toplevel list info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForeignToplevelListInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) toplevels: Vec<ForeignToplevelInfo>,
+}
+
+// Foreign toplevel info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForeignToplevelInfo {
+    #[serde(skip_serializing)]
+    pub(crate) handle: ExtForeignToplevelHandleV1,
+    pub(crate) title: String,
+    pub(crate) app_id: String,
+    pub(crate) identifier: String,
+    pub(crate) capture_constraints: Option<CaptureConstraintsInfo>,
+}
+
+impl AppData {
+    pub(crate) fn add_foreign_toplevel_list(&mut self, name: u32) {
+        self.foreign_toplevel_lists.push(ForeignToplevelListInfo {
+            name,
+            toplevels: Vec::new(),
+        });
+    }
+
+    pub(crate) fn add_foreign_toplevel(
+        &mut self,
+        list_index: usize,
+        handle: ExtForeignToplevelHandleV1,
+    ) {
+        if let Some(list) = self.foreign_toplevel_lists.get_mut(list_index) {
+            list.toplevels.push(ForeignToplevelInfo {
+                handle,
+                title: String::new(),
+                app_id: String::new(),
+                identifier: String::new(),
+                capture_constraints: None,
+            });
+        }
+    }
+
+    fn foreign_toplevel_mut(
+        &mut self,
+        handle: &ExtForeignToplevelHandleV1,
+    ) -> Option<&mut ForeignToplevelInfo> {
+        self.foreign_toplevel_lists
+            .iter_mut()
+            .flat_map(|list| list.toplevels.iter_mut())
+            .find(|toplevel| &toplevel.handle == handle)
+    }
+
+    /// Ask the compositor to stop sending new toplevels; it answers with `finished`.
+    pub(crate) fn stop_foreign_toplevel_lists(&self) {
+        for list in &self.foreign_toplevel_list_objects {
+            list.stop();
+        }
+    }
+}
+
+// Handle ext_foreign_toplevel_list_v1 events
+impl Dispatch<ExtForeignToplevelListV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        list: &ExtForeignToplevelListV1,
+        event: ext_foreign_toplevel_list_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::ForeignToplevelList { list_index } = data {
+            state.mark_event();
+            match event {
+                ext_foreign_toplevel_list_v1::Event::Toplevel { toplevel } => {
+                    state.add_foreign_toplevel(*list_index, toplevel);
+                }
+                ext_foreign_toplevel_list_v1::Event::Finished => {
+                    list.destroy();
+                }
+                _ => {}
+            }
+        }
+    }
+
+    event_created_child!(AppData, ExtForeignToplevelListV1, [
+        ext_foreign_toplevel_list_v1::EVT_TOPLEVEL_OPCODE => (ExtForeignToplevelHandleV1, UserData::ForeignToplevel),
+    ]);
+}
+
+// Handle ext_foreign_toplevel_handle_v1 events
+impl Dispatch<ExtForeignToplevelHandleV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        handle: &ExtForeignToplevelHandleV1,
+        event: ext_foreign_toplevel_handle_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::ForeignToplevel = data {
+            state.mark_event();
+            if let ext_foreign_toplevel_handle_v1::Event::Closed = event {
+                if let Some(constraints) = state
+                    .foreign_toplevel_mut(handle)
+                    .and_then(|toplevel| toplevel.capture_constraints.as_mut())
+                    .filter(|constraints| constraints.state == "pending")
+                {
+                    constraints.finish("closed");
+                }
+                for list in &mut state.foreign_toplevel_lists {
+                    list.toplevels.retain(|toplevel| &toplevel.handle != handle);
+                }
+                handle.destroy();
+                return;
+            }
+            // The initial state is complete; open a capture session for the window
+            if let ext_foreign_toplevel_handle_v1::Event::Done = event {
+                state.get_toplevel_capture_session(handle, qh);
+            }
+            let Some(toplevel) = state.foreign_toplevel_mut(handle) else {
+                return;
+            };
+            match event {
+                ext_foreign_toplevel_handle_v1::Event::Title { title } => {
+                    toplevel.title = title;
+                }
+                ext_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                    toplevel.app_id = app_id;
+                }
+                ext_foreign_toplevel_handle_v1::Event::Identifier { identifier } => {
+                    toplevel.identifier = identifier;
+                }
+                _ => {}
+            }
+        }
+    }
+}
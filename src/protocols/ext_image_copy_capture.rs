@@ -0,0 +1,371 @@
+use serde::Serialize;
+use wayland_client::{Connection, Dispatch, QueueHandle, WEnum};
+use wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_handle_v1::ExtForeignToplevelHandleV1;
+use wayland_protocols::ext::image_capture_source::v1::client::{
+    ext_foreign_toplevel_image_capture_source_manager_v1::{
+        self, ExtForeignToplevelImageCaptureSourceManagerV1,
+    },
+    ext_image_capture_source_v1::{self, ExtImageCaptureSourceV1},
+    ext_output_image_capture_source_manager_v1::{self, ExtOutputImageCaptureSourceManagerV1},
+};
+use wayland_protocols::ext::image_copy_capture::v1::client::{
+    ext_image_copy_capture_frame_v1::{self, ExtImageCopyCaptureFrameV1},
+    ext_image_copy_capture_manager_v1::{self, ExtImageCopyCaptureManagerV1},
+    ext_image_copy_capture_session_v1::{self, ExtImageCopyCaptureSessionV1},
+};
+
+use crate::app::{AppData, UserData};
+use crate::capture_probe::PresentationTime;
+use crate::drm_device::DrmDevice;
+use crate::protocols::linux_dmabuf::{modifier_to_string, DmabufFormat};
+use crate::protocols::wl_output::transform_to_string;
+use crate::protocols::wl_shm::{format_to_fourcc, ShmFormat};
+
+// Buffer constraints advertised by an image copy capture session
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureConstraintsInfo {
+    #[serde(skip_serializing)]
+    pub(crate) source: ExtImageCaptureSourceV1,
+    #[serde(skip_serializing)]
+    pub(crate) session: ExtImageCopyCaptureSessionV1,
+    pub(crate) state: String,
+    pub(crate) buffer_size: Option<CaptureBufferSize>,
+    pub(crate) shm_formats: Vec<ShmFormat>,
+    pub(crate) dmabuf_device: Option<DrmDevice>,
+    pub(crate) dmabuf_formats: Vec<DmabufFormat>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CaptureBufferSize {
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+impl CaptureConstraintsInfo {
+    pub(crate) fn new(
+        source: ExtImageCaptureSourceV1,
+        session: ExtImageCopyCaptureSessionV1,
+    ) -> Self {
+        Self {
+            source,
+            session,
+            state: "pending".to_string(),
+            buffer_size: None,
+            shm_formats: Vec::new(),
+            dmabuf_device: None,
+            dmabuf_formats: Vec::new(),
+        }
+    }
+
+    // Close the session and its source without capturing, recording why
+    pub(crate) fn finish(&mut self, state: &str) {
+        self.state = state.to_string();
+        self.session.destroy();
+        self.source.destroy();
+    }
+}
+
+impl AppData {
+    /// Open an image copy capture session for every bound output to collect its
+    /// buffer constraints. No frame is ever captured.
+    pub(crate) fn get_output_capture_sessions(&mut self, qh: &QueueHandle<AppData>) {
+        let (Some(source_manager), Some(capture_manager)) = (
+            self.output_capture_source_manager_objects.first(),
+            self.image_copy_capture_manager_objects.first(),
+        ) else {
+            return;
+        };
+        for (output_index, output) in self.output_objects.iter().enumerate() {
+            let source = source_manager.create_source(output, qh, UserData::ImageCaptureSource);
+            let session = capture_manager.create_session(
+                &source,
+                ext_image_copy_capture_manager_v1::Options::empty(),
+                qh,
+                UserData::OutputCaptureSession { output_index },
+            );
+            if let Some(info) = self.outputs.get_mut(output_index) {
+                info.capture_constraints = Some(CaptureConstraintsInfo::new(source, session));
+            }
+        }
+    }
+
+    /// Open an image copy capture session for a toplevel once its initial state is known.
+    /// Windows that already have a session are skipped.
+    pub(crate) fn get_toplevel_capture_session(
+        &mut self,
+        handle: &ExtForeignToplevelHandleV1,
+        qh: &QueueHandle<AppData>,
+    ) {
+        let (Some(source_manager), Some(capture_manager)) = (
+            self.toplevel_capture_source_manager_objects
+                .first()
+                .cloned(),
+            self.image_copy_capture_manager_objects.first().cloned(),
+        ) else {
+            return;
+        };
+        let Some(toplevel) = self
+            .foreign_toplevel_lists
+            .iter_mut()
+            .flat_map(|list| list.toplevels.iter_mut())
+            .find(|toplevel| &toplevel.handle == handle)
+            .filter(|toplevel| toplevel.capture_constraints.is_none())
+        else {
+            return;
+        };
+        let source = source_manager.create_source(handle, qh, UserData::ImageCaptureSource);
+        let session = capture_manager.create_session(
+            &source,
+            ext_image_copy_capture_manager_v1::Options::empty(),
+            qh,
+            UserData::ToplevelCaptureSession,
+        );
+        toplevel.capture_constraints = Some(CaptureConstraintsInfo::new(source, session));
+    }
+
+    // Output and probe sessions are indexed; toplevel sessions are looked up by proxy
+    fn capture_constraints_mut(
+        &mut self,
+        session: &ExtImageCopyCaptureSessionV1,
+        data: &UserData,
+    ) -> Option<&mut CaptureConstraintsInfo> {
+        match data {
+            UserData::OutputCaptureSession { output_index } => self
+                .outputs
+                .get_mut(*output_index)?
+                .capture_constraints
+                .as_mut(),
+            UserData::CaptureProbeSession { probe_index } => self
+                .capture_probe_results
+                .get_mut(*probe_index)?
+                .session
+                .as_mut(),
+            UserData::ToplevelCaptureSession => self
+                .foreign_toplevel_lists
+                .iter_mut()
+                .flat_map(|list| list.toplevels.iter_mut())
+                .filter_map(|toplevel| toplevel.capture_constraints.as_mut())
+                .find(|constraints| &constraints.session == session),
+            _ => None,
+        }
+    }
+}
+
+// Handle ext_output_image_capture_source_manager_v1 events
+impl Dispatch<ExtOutputImageCaptureSourceManagerV1, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _manager: &ExtOutputImageCaptureSourceManagerV1,
+        _event: ext_output_image_capture_source_manager_v1::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // ExtOutputImageCaptureSourceManagerV1 has no events.
+    }
+}
+
+// Handle ext_foreign_toplevel_image_capture_source_manager_v1 events
+impl Dispatch<ExtForeignToplevelImageCaptureSourceManagerV1, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _manager: &ExtForeignToplevelImageCaptureSourceManagerV1,
+        _event: ext_foreign_toplevel_image_capture_source_manager_v1::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // ExtForeignToplevelImageCaptureSourceManagerV1 has no events.
+    }
+}
+
+// Handle ext_image_capture_source_v1 events
+impl Dispatch<ExtImageCaptureSourceV1, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _source: &ExtImageCaptureSourceV1,
+        _event: ext_image_capture_source_v1::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // ExtImageCaptureSourceV1 has no events; it is an opaque handle.
+    }
+}
+
+// Handle ext_image_copy_capture_manager_v1 events
+impl Dispatch<ExtImageCopyCaptureManagerV1, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _manager: &ExtImageCopyCaptureManagerV1,
+        _event: ext_image_copy_capture_manager_v1::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // ExtImageCopyCaptureManagerV1 has no events.
+    }
+}
+
+// Handle ext_image_copy_capture_session_v1 events
+impl Dispatch<ExtImageCopyCaptureSessionV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        session: &ExtImageCopyCaptureSessionV1,
+        event: ext_image_copy_capture_session_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        state.mark_event();
+        let sysfs_root = state.sysfs_root.clone();
+        let Some(info) = state.capture_constraints_mut(session, data) else {
+            return;
+        };
+        match event {
+            ext_image_copy_capture_session_v1::Event::BufferSize { width, height } => {
+                info.buffer_size = Some(CaptureBufferSize { width, height });
+            }
+            ext_image_copy_capture_session_v1::Event::ShmFormat { format } => {
+                let format = match format {
+                    WEnum::Value(value) => value as u32,
+                    WEnum::Unknown(value) => value,
+                };
+                info.shm_formats.push(ShmFormat {
+                    format,
+                    fourcc: format_to_fourcc(format),
+                });
+            }
+            ext_image_copy_capture_session_v1::Event::DmabufDevice { device } => {
+                info.dmabuf_device = DrmDevice::from_dev_t_bytes(&device, &sysfs_root);
+            }
+            ext_image_copy_capture_session_v1::Event::DmabufFormat { format, modifiers } => {
+                info.dmabuf_formats.push(DmabufFormat {
+                    format,
+                    fourcc: format_to_fourcc(format),
+                    modifiers: modifiers_to_strings(&modifiers),
+                });
+            }
+            // Probe sessions stay open to capture a frame
+            ext_image_copy_capture_session_v1::Event::Done
+                if matches!(data, UserData::CaptureProbeSession { .. }) =>
+            {
+                info.state = "done".to_string();
+            }
+            // The constraints are complete; close the session before any frame
+            // is requested
+            ext_image_copy_capture_session_v1::Event::Done => {
+                info.finish("done");
+            }
+            // The source went away or refuses capture
+            ext_image_copy_capture_session_v1::Event::Stopped => {
+                info.finish("stopped");
+            }
+            _ => {}
+        }
+    }
+}
+
+// Handle ext_image_copy_capture_frame_v1 events; frames are only created by the capture probe
+impl Dispatch<ExtImageCopyCaptureFrameV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        frame: &ExtImageCopyCaptureFrameV1,
+        event: ext_image_copy_capture_frame_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::CaptureProbeFrame { probe_index } = data {
+            state.mark_event();
+            let Some(result) = state.capture_probe_results.get_mut(*probe_index) else {
+                return;
+            };
+            match event {
+                ext_image_copy_capture_frame_v1::Event::Transform { transform } => {
+                    result.transform = Some(transform_to_string(transform));
+                }
+                ext_image_copy_capture_frame_v1::Event::PresentationTime {
+                    tv_sec_hi,
+                    tv_sec_lo,
+                    tv_nsec,
+                } => {
+                    result.presentation_time =
+                        Some(PresentationTime::new(tv_sec_hi, tv_sec_lo, tv_nsec));
+                }
+                ext_image_copy_capture_frame_v1::Event::Ready => {
+                    result.status = "ready".to_string();
+                    result.capture_frame = None;
+                    frame.destroy();
+                }
+                ext_image_copy_capture_frame_v1::Event::Failed { reason } => {
+                    result.fail(failure_reason_to_string(reason));
+                    result.capture_frame = None;
+                    frame.destroy();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+fn failure_reason_to_string(
+    reason: WEnum<ext_image_copy_capture_frame_v1::FailureReason>,
+) -> String {
+    match reason {
+        WEnum::Value(ext_image_copy_capture_frame_v1::FailureReason::Unknown) => "unknown",
+        WEnum::Value(ext_image_copy_capture_frame_v1::FailureReason::BufferConstraints) => {
+            "buffer_constraints"
+        }
+        WEnum::Value(ext_image_copy_capture_frame_v1::FailureReason::Stopped) => "stopped",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+// The dmabuf_format modifier array holds native-endian u64 values
+fn modifiers_to_strings(modifiers: &[u8]) -> Vec<String> {
+    modifiers
+        .chunks_exact(8)
+        .map(|c| modifier_to_string(u64::from_ne_bytes(c.try_into().unwrap())))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn modifier_array_decodes_in_order() {
+        let array: Vec<u8> = [0x0100_0000_0000_0004u64, 0]
+            .iter()
+            .flat_map(|m| m.to_ne_bytes())
+            .collect();
+        assert_eq!(
+            modifiers_to_strings(&array),
+            ["0x0100000000000004", "linear"]
+        );
+    }
+
+    #[test]
+    fn empty_or_short_modifier_array_has_no_modifiers() {
+        assert!(modifiers_to_strings(&[]).is_empty());
+        assert!(modifiers_to_strings(&[0; 7]).is_empty());
+    }
+
+    #[test]
+    fn failure_reasons_use_protocol_names() {
+        use ext_image_copy_capture_frame_v1::FailureReason;
+        assert_eq!(
+            failure_reason_to_string(WEnum::Value(FailureReason::BufferConstraints)),
+            "buffer_constraints"
+        );
+        assert_eq!(
+            failure_reason_to_string(WEnum::Value(FailureReason::Stopped)),
+            "stopped"
+        );
+        assert_eq!(failure_reason_to_string(WEnum::Unknown(7)), "unknown");
+    }
+}
@@ -0,0 +1,298 @@
+use serde::Serialize;
+use wayland_client::{
+    event_created_child, protocol::wl_output::WlOutput, Connection, Dispatch, QueueHandle, WEnum,
+};
+use wayland_protocols::ext::workspace::v1::client::{
+    ext_workspace_group_handle_v1::{self, ExtWorkspaceGroupHandleV1},
+    ext_workspace_handle_v1::{self, ExtWorkspaceHandleV1},
+    ext_workspace_manager_v1::{self, ExtWorkspaceManagerV1},
+};
+
+use crate::app::{AppData, UserData};
+
+// Workspace manager info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceManagerInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) groups: Vec<WorkspaceGroupInfo>,
+    pub(crate) ungrouped_workspaces: Vec<WorkspaceInfo>,
+    #[serde(skip_serializing)]
+    pub(crate) workspaces: Vec<WorkspaceInfo>,
+}
+
+// Workspace group info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceGroupInfo {
+    #[serde(skip_serializing)]
+    pub(crate) handle: ExtWorkspaceGroupHandleV1,
+    pub(crate) capabilities: Vec<String>,
+    pub(crate) outputs: Vec<String>,
+    pub(crate) workspaces: Vec<WorkspaceInfo>,
+    #[serde(skip_serializing)]
+    pub(crate) output_objects: Vec<WlOutput>,
+    #[serde(skip_serializing)]
+    pub(crate) workspace_handles: Vec<ExtWorkspaceHandleV1>,
+}
+
+// Workspace info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceInfo {
+    #[serde(skip_serializing)]
+    pub(crate) handle: ExtWorkspaceHandleV1,
+    pub(crate) id: Option<String>,
+    pub(crate) name: String,
+    pub(crate) coordinates: Vec<u32>,
+    pub(crate) state: Vec<String>,
+    pub(crate) capabilities: Vec<String>,
+}
+
+impl AppData {
+    pub(crate) fn add_workspace_manager(&mut self, name: u32) {
+        self.workspace_managers.push(WorkspaceManagerInfo {
+            name,
+            groups: Vec::new(),
+            ungrouped_workspaces: Vec::new(),
+            workspaces: Vec::new(),
+        });
+    }
+
+    pub(crate) fn add_workspace_group(
+        &mut self,
+        manager_index: usize,
+        handle: ExtWorkspaceGroupHandleV1,
+    ) {
+        if let Some(manager) = self.workspace_managers.get_mut(manager_index) {
+            manager.groups.push(WorkspaceGroupInfo {
+                handle,
+                capabilities: Vec::new(),
+                outputs: Vec::new(),
+                workspaces: Vec::new(),
+                output_objects: Vec::new(),
+                workspace_handles: Vec::new(),
+            });
+        }
+    }
+
+    pub(crate) fn add_workspace(&mut self, manager_index: usize, handle: ExtWorkspaceHandleV1) {
+        if let Some(manager) = self.workspace_managers.get_mut(manager_index) {
+            manager.workspaces.push(WorkspaceInfo {
+                handle,
+                id: None,
+                name: String::new(),
+                coordinates: Vec::new(),
+                state: Vec::new(),
+                capabilities: Vec::new(),
+            });
+        }
+    }
+
+    fn workspace_group_mut(
+        &mut self,
+        handle: &ExtWorkspaceGroupHandleV1,
+    ) -> Option<&mut WorkspaceGroupInfo> {
+        self.workspace_managers
+            .iter_mut()
+            .flat_map(|manager| manager.groups.iter_mut())
+            .find(|group| &group.handle == handle)
+    }
+
+    fn workspace_mut(&mut self, handle: &ExtWorkspaceHandleV1) -> Option<&mut WorkspaceInfo> {
+        self.workspace_managers
+            .iter_mut()
+            .flat_map(|manager| manager.workspaces.iter_mut())
+            .find(|workspace| &workspace.handle == handle)
+    }
+
+    /// Nest workspaces under their groups and name group outputs after `OutputInfo`.
+    /// Call once all events have been dispatched.
+    pub(crate) fn resolve_workspaces(&mut self) {
+        let mut managers = std::mem::take(&mut self.workspace_managers);
+        for manager in &mut managers {
+            for group in &mut manager.groups {
+                group.outputs = group
+                    .output_objects
+                    .iter()
+                    .filter_map(|output| self.output_name(output))
+                    .collect();
+                group.workspaces = manager
+                    .workspaces
+                    .iter()
+                    .filter(|workspace| group.workspace_handles.contains(&workspace.handle))
+                    .cloned()
+                    .collect();
+            }
+            manager.ungrouped_workspaces = manager
+                .workspaces
+                .iter()
+                .filter(|workspace| {
+                    !manager
+                        .groups
+                        .iter()
+                        .any(|group| group.workspace_handles.contains(&workspace.handle))
+                })
+                .cloned()
+                .collect();
+        }
+        self.workspace_managers = managers;
+    }
+}
+
+fn bits_to_strings(bits: u32, names: &[&str]) -> Vec<String> {
+    names
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| bits & (1 << bit) != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+// Handle ext_workspace_manager_v1 events
+impl Dispatch<ExtWorkspaceManagerV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _manager: &ExtWorkspaceManagerV1,
+        event: ext_workspace_manager_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::WorkspaceManager { manager_index } = data {
+            state.mark_event();
+            match event {
+                ext_workspace_manager_v1::Event::WorkspaceGroup { workspace_group } => {
+                    state.add_workspace_group(*manager_index, workspace_group);
+                }
+                ext_workspace_manager_v1::Event::Workspace { workspace } => {
+                    state.add_workspace(*manager_index, workspace);
+                }
+                ext_workspace_manager_v1::Event::Done => {}
+                _ => {}
+            }
+        }
+    }
+
+    event_created_child!(AppData, ExtWorkspaceManagerV1, [
+        ext_workspace_manager_v1::EVT_WORKSPACE_GROUP_OPCODE => (ExtWorkspaceGroupHandleV1, UserData::WorkspaceGroup),
+        ext_workspace_manager_v1::EVT_WORKSPACE_OPCODE => (ExtWorkspaceHandleV1, UserData::Workspace),
+    ]);
+}
+
+// Handle ext_workspace_group_handle_v1 events
+impl Dispatch<ExtWorkspaceGroupHandleV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        handle: &ExtWorkspaceGroupHandleV1,
+        event: ext_workspace_group_handle_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::WorkspaceGroup = data {
+            state.mark_event();
+            if let ext_workspace_group_handle_v1::Event::Removed = event {
+                for manager in &mut state.workspace_managers {
+                    manager.groups.retain(|group| &group.handle != handle);
+                }
+                return;
+            }
+            let Some(group) = state.workspace_group_mut(handle) else {
+                return;
+            };
+            match event {
+                ext_workspace_group_handle_v1::Event::Capabilities { capabilities } => {
+                    let bits = match capabilities {
+                        WEnum::Value(v) => v.bits(),
+                        WEnum::Unknown(v) => v,
+                    };
+                    group.capabilities = bits_to_strings(bits, &["create_workspace"]);
+                }
+                ext_workspace_group_handle_v1::Event::OutputEnter { output } => {
+                    group.output_objects.push(output);
+                }
+                ext_workspace_group_handle_v1::Event::OutputLeave { output } => {
+                    group.output_objects.retain(|o| o != &output);
+                }
+                ext_workspace_group_handle_v1::Event::WorkspaceEnter { workspace } => {
+                    group.workspace_handles.push(workspace);
+                }
+                ext_workspace_group_handle_v1::Event::WorkspaceLeave { workspace } => {
+                    group.workspace_handles.retain(|w| w != &workspace);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// Handle ext_workspace_handle_v1 events
+impl Dispatch<ExtWorkspaceHandleV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        handle: &ExtWorkspaceHandleV1,
+        event: ext_workspace_handle_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::Workspace = data {
+            state.mark_event();
+            if let ext_workspace_handle_v1::Event::Removed = event {
+                for manager in &mut state.workspace_managers {
+                    manager.workspaces.retain(|w| &w.handle != handle);
+                }
+                return;
+            }
+            let Some(workspace) = state.workspace_mut(handle) else {
+                return;
+            };
+            match event {
+                ext_workspace_handle_v1::Event::Id { id } => {
+                    workspace.id = Some(id);
+                }
+                ext_workspace_handle_v1::Event::Name { name } => {
+                    workspace.name = name;
+                }
+                ext_workspace_handle_v1::Event::Coordinates { coordinates } => {
+                    workspace.coordinates = coordinates
+                        .chunks_exact(4)
+                        .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+                        .collect();
+                }
+                ext_workspace_handle_v1::Event::State { state } => {
+                    let bits = match state {
+                        WEnum::Value(v) => v.bits(),
+                        WEnum::Unknown(v) => v,
+                    };
+                    workspace.state = bits_to_strings(bits, &["active", "urgent", "hidden"]);
+                }
+                ext_workspace_handle_v1::Event::Capabilities { capabilities } => {
+                    let bits = match capabilities {
+                        WEnum::Value(v) => v.bits(),
+                        WEnum::Unknown(v) => v,
+                    };
+                    workspace.capabilities =
+                        bits_to_strings(bits, &["activate", "deactivate", "remove", "assign"]);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bits_follow_the_protocol_enum_order() {
+        let state = ["active", "urgent", "hidden"];
+        assert!(bits_to_strings(0, &state).is_empty());
+        assert_eq!(bits_to_strings(0b101, &state), ["active", "hidden"]);
+        // Bits added in later protocol versions are not named yet
+        assert_eq!(bits_to_strings(0b1010, &state), ["urgent"]);
+    }
+}
@@ -0,0 +1,459 @@
+use base64::Engine;
+use serde::Serialize;
+use wayland_client::{event_created_child, Connection, Dispatch, QueueHandle, WEnum};
+use wayland_protocols_plasma::output_device::v2::client::{
+    kde_output_device_mode_v2::{self, KdeOutputDeviceModeV2},
+    kde_output_device_v2::{self, KdeOutputDeviceV2},
+};
+
+use crate::app::{AppData, UserData};
+use crate::protocols::wl_output::{subpixel_to_string, transform_to_string};
+
+// Size of an EDID base block
+const EDID_BLOCK_SIZE: usize = 128;
+
+// KDE output device info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KdeOutputDeviceInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) output_name: String,
+    pub(crate) uuid: String,
+    pub(crate) make: String,
+    pub(crate) model: String,
+    pub(crate) serial_number: String,
+    pub(crate) eisa_id: String,
+    pub(crate) enabled: bool,
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) physical_width: i32,
+    pub(crate) physical_height: i32,
+    pub(crate) subpixel_orientation: String,
+    pub(crate) output_transform: String,
+    pub(crate) scale: f64,
+    pub(crate) capabilities: Vec<String>,
+    pub(crate) overscan: Option<u32>,
+    pub(crate) vrr_policy: Option<String>,
+    pub(crate) rgb_range: Option<String>,
+    pub(crate) high_dynamic_range: Option<bool>,
+    pub(crate) sdr_brightness: Option<u32>,
+    pub(crate) wide_color_gamut: Option<bool>,
+    pub(crate) auto_rotate_policy: Option<String>,
+    pub(crate) icc_profile_path: Option<String>,
+    pub(crate) color_profile_source: Option<String>,
+    pub(crate) brightness: Option<u32>,
+    #[serde(serialize_with = "serialize_hex")]
+    pub(crate) edid: Vec<u8>,
+    pub(crate) modes: Vec<KdeOutputDeviceModeInfo>,
+}
+
+// KDE output device mode info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KdeOutputDeviceModeInfo {
+    #[serde(skip_serializing)]
+    pub(crate) mode: KdeOutputDeviceModeV2,
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+    pub(crate) refresh: i32,
+    pub(crate) preferred: bool,
+    pub(crate) current: bool,
+}
+
+// Identity fields decoded from an EDID base block
+#[derive(Debug, Clone)]
+pub struct EdidSummary {
+    pub(crate) vendor: String,
+    pub(crate) product_code: u16,
+    pub(crate) serial: u32,
+    pub(crate) monitor_name: Option<String>,
+    pub(crate) serial_string: Option<String>,
+}
+
+impl AppData {
+    pub(crate) fn add_kde_output_device(&mut self, name: u32) {
+        self.kde_output_devices.push(KdeOutputDeviceInfo {
+            name,
+            output_name: String::new(),
+            uuid: String::new(),
+            make: String::new(),
+            model: String::new(),
+            serial_number: String::new(),
+            eisa_id: String::new(),
+            enabled: false,
+            x: 0,
+            y: 0,
+            physical_width: 0,
+            physical_height: 0,
+            subpixel_orientation: String::new(),
+            output_transform: String::new(),
+            scale: 1.0,
+            capabilities: Vec::new(),
+            overscan: None,
+            vrr_policy: None,
+            rgb_range: None,
+            high_dynamic_range: None,
+            sdr_brightness: None,
+            wide_color_gamut: None,
+            auto_rotate_policy: None,
+            icc_profile_path: None,
+            color_profile_source: None,
+            brightness: None,
+            edid: Vec::new(),
+            modes: Vec::new(),
+        });
+    }
+
+    fn kde_output_device_mode_mut(
+        &mut self,
+        mode: &KdeOutputDeviceModeV2,
+    ) -> Option<&mut KdeOutputDeviceModeInfo> {
+        self.kde_output_devices
+            .iter_mut()
+            .flat_map(|device| device.modes.iter_mut())
+            .find(|info| &info.mode == mode)
+    }
+}
+
+impl KdeOutputDeviceInfo {
+    // Decode the vendor, product and serial from the EDID blob
+    pub(crate) fn edid_summary(&self) -> Option<EdidSummary> {
+        parse_edid(&self.edid)
+    }
+}
+
+fn parse_edid(edid: &[u8]) -> Option<EdidSummary> {
+    const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+    if edid.len() < EDID_BLOCK_SIZE || edid[..8] != HEADER {
+        return None;
+    }
+
+    // Manufacturer ID: three 5-bit letters, big endian
+    let id = u16::from_be_bytes([edid[8], edid[9]]);
+    let vendor = [(id >> 10) & 0x1f, (id >> 5) & 0x1f, id & 0x1f]
+        .iter()
+        .map(|&c| (b'A' - 1 + c as u8) as char)
+        .collect();
+
+    let mut summary = EdidSummary {
+        vendor,
+        product_code: u16::from_le_bytes([edid[10], edid[11]]),
+        serial: u32::from_le_bytes([edid[12], edid[13], edid[14], edid[15]]),
+        monitor_name: None,
+        serial_string: None,
+    };
+
+    // Display descriptors: four 18-byte blocks starting at offset 54
+    for descriptor in edid[54..126].chunks_exact(18) {
+        if descriptor[..3] != [0, 0, 0] {
+            continue;
+        }
+        let text = String::from_utf8_lossy(&descriptor[5..])
+            .split('\n')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        match descriptor[3] {
+            0xfc => summary.monitor_name = Some(text),
+            0xff => summary.serial_string = Some(text),
+            _ => {}
+        }
+    }
+
+    Some(summary)
+}
+
+// Embed binary blobs as lowercase hex in JSON
+fn serialize_hex<S: serde::Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    serializer.serialize_str(&hex)
+}
+
+fn capabilities_to_strings(flags: u32) -> Vec<String> {
+    const NAMES: [&str; 8] = [
+        "overscan",
+        "vrr",
+        "rgb_range",
+        "high_dynamic_range",
+        "wide_color_gamut",
+        "auto_rotate",
+        "icc_profile",
+        "brightness",
+    ];
+    NAMES
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| flags & (1 << bit) != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+fn vrr_policy_to_string(policy: WEnum<kde_output_device_v2::VrrPolicy>) -> String {
+    match policy {
+        WEnum::Value(kde_output_device_v2::VrrPolicy::Never) => "never".to_string(),
+        WEnum::Value(kde_output_device_v2::VrrPolicy::Always) => "always".to_string(),
+        WEnum::Value(kde_output_device_v2::VrrPolicy::Automatic) => "automatic".to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn rgb_range_to_string(range: WEnum<kde_output_device_v2::RgbRange>) -> String {
+    match range {
+        WEnum::Value(kde_output_device_v2::RgbRange::Automatic) => "automatic".to_string(),
+        WEnum::Value(kde_output_device_v2::RgbRange::Full) => "full".to_string(),
+        WEnum::Value(kde_output_device_v2::RgbRange::Limited) => "limited".to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn auto_rotate_policy_to_string(policy: WEnum<kde_output_device_v2::AutoRotatePolicy>) -> String {
+    match policy {
+        WEnum::Value(kde_output_device_v2::AutoRotatePolicy::Never) => "never".to_string(),
+        WEnum::Value(kde_output_device_v2::AutoRotatePolicy::InTabletMode) => {
+            "in_tablet_mode".to_string()
+        }
+        WEnum::Value(kde_output_device_v2::AutoRotatePolicy::Always) => "always".to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+fn color_profile_source_to_string(
+    source: WEnum<kde_output_device_v2::ColorProfileSource>,
+) -> String {
+    match source {
+        WEnum::Value(kde_output_device_v2::ColorProfileSource::SRGB) => "srgb".to_string(),
+        WEnum::Value(kde_output_device_v2::ColorProfileSource::ICC) => "icc".to_string(),
+        WEnum::Value(kde_output_device_v2::ColorProfileSource::EDID) => "edid".to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+// Handle kde_output_device_v2 events
+impl Dispatch<KdeOutputDeviceV2, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _device: &KdeOutputDeviceV2,
+        event: kde_output_device_v2::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::KdeOutputDevice { device_index } = data {
+            state.mark_event();
+            let Some(info) = state.kde_output_devices.get_mut(*device_index) else {
+                return;
+            };
+            match event {
+                kde_output_device_v2::Event::Geometry {
+                    x,
+                    y,
+                    physical_width,
+                    physical_height,
+                    subpixel,
+                    make,
+                    model,
+                    transform,
+                } => {
+                    info.x = x;
+                    info.y = y;
+                    info.physical_width = physical_width;
+                    info.physical_height = physical_height;
+                    info.subpixel_orientation = subpixel_to_string(WEnum::from(subpixel as u32));
+                    info.output_transform = transform_to_string(WEnum::from(transform as u32));
+                    info.make = make;
+                    info.model = model;
+                }
+                kde_output_device_v2::Event::CurrentMode { mode } => {
+                    for info_mode in &mut info.modes {
+                        info_mode.current = info_mode.mode == mode;
+                    }
+                }
+                kde_output_device_v2::Event::Mode { mode } => {
+                    info.modes.push(KdeOutputDeviceModeInfo {
+                        mode,
+                        width: 0,
+                        height: 0,
+                        refresh: 0,
+                        preferred: false,
+                        current: false,
+                    });
+                }
+                kde_output_device_v2::Event::Scale { factor } => {
+                    info.scale = factor;
+                }
+                kde_output_device_v2::Event::Edid { raw } => {
+                    info.edid = base64::engine::general_purpose::STANDARD
+                        .decode(raw.trim())
+                        .unwrap_or_default();
+                }
+                kde_output_device_v2::Event::Enabled { enabled } => {
+                    info.enabled = enabled != 0;
+                }
+                kde_output_device_v2::Event::Uuid { uuid } => {
+                    info.uuid = uuid;
+                }
+                kde_output_device_v2::Event::SerialNumber { serialNumber } => {
+                    info.serial_number = serialNumber;
+                }
+                kde_output_device_v2::Event::EisaId { eisaId } => {
+                    info.eisa_id = eisaId;
+                }
+                kde_output_device_v2::Event::Capabilities { flags } => {
+                    let flags_value = match flags {
+                        WEnum::Value(v) => v.bits(),
+                        WEnum::Unknown(v) => v,
+                    };
+                    info.capabilities = capabilities_to_strings(flags_value);
+                }
+                kde_output_device_v2::Event::Overscan { overscan } => {
+                    info.overscan = Some(overscan);
+                }
+                kde_output_device_v2::Event::VrrPolicy { vrr_policy } => {
+                    info.vrr_policy = Some(vrr_policy_to_string(vrr_policy));
+                }
+                kde_output_device_v2::Event::RgbRange { rgb_range } => {
+                    info.rgb_range = Some(rgb_range_to_string(rgb_range));
+                }
+                kde_output_device_v2::Event::Name { name } => {
+                    info.output_name = name;
+                }
+                kde_output_device_v2::Event::HighDynamicRange { hdr_enabled } => {
+                    info.high_dynamic_range = Some(hdr_enabled != 0);
+                }
+                kde_output_device_v2::Event::SdrBrightness { sdr_brightness } => {
+                    info.sdr_brightness = Some(sdr_brightness);
+                }
+                kde_output_device_v2::Event::WideColorGamut { wcg_enabled } => {
+                    info.wide_color_gamut = Some(wcg_enabled != 0);
+                }
+                kde_output_device_v2::Event::AutoRotatePolicy { policy } => {
+                    info.auto_rotate_policy = Some(auto_rotate_policy_to_string(policy));
+                }
+                kde_output_device_v2::Event::IccProfilePath { profile_path } => {
+                    info.icc_profile_path = Some(profile_path);
+                }
+                kde_output_device_v2::Event::ColorProfileSource { source } => {
+                    info.color_profile_source = Some(color_profile_source_to_string(source));
+                }
+                kde_output_device_v2::Event::Brightness { brightness } => {
+                    info.brightness = Some(brightness);
+                }
+                kde_output_device_v2::Event::Done => {}
+                _ => {}
+            }
+        }
+    }
+
+    event_created_child!(AppData, KdeOutputDeviceV2, [
+        kde_output_device_v2::EVT_MODE_OPCODE => (KdeOutputDeviceModeV2, UserData::KdeOutputDeviceMode),
+    ]);
+}
+
+// Handle kde_output_device_mode_v2 events
+impl Dispatch<KdeOutputDeviceModeV2, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        mode: &KdeOutputDeviceModeV2,
+        event: kde_output_device_mode_v2::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::KdeOutputDeviceMode = data {
+            state.mark_event();
+            if let kde_output_device_mode_v2::Event::Removed = event {
+                for device in &mut state.kde_output_devices {
+                    device.modes.retain(|info| &info.mode != mode);
+                }
+                return;
+            }
+            let Some(info) = state.kde_output_device_mode_mut(mode) else {
+                return;
+            };
+            match event {
+                kde_output_device_mode_v2::Event::Size { width, height } => {
+                    info.width = width;
+                    info.height = height;
+                }
+                kde_output_device_mode_v2::Event::Refresh { refresh } => {
+                    info.refresh = refresh;
+                }
+                kde_output_device_mode_v2::Event::Preferred => {
+                    info.preferred = true;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Base block (plus extension count) in the layout a DisplayPort 4K monitor
+    // sends: a detailed timing, a serial string, a name and range limits
+    const DELL_U2720Q: &str = "\
+        00ffffffffffff0010acc5a04c4c30300c1e0104b53c22783a4d55a7554c9e250c5054a54b00\
+        714f8180a9c0d1c001010101010101014dd000a0f0703e8030203500544f2100001a000000ff\
+        00434e304142433132330a202020000000fc0044454c4c205532373230510a20000000fd0018\
+        4b1e8c36010a2020202020200141";
+
+    fn decode_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn decodes_monitor_identity() {
+        let edid = decode_hex(DELL_U2720Q);
+        let summary = parse_edid(&edid).unwrap();
+        assert_eq!(summary.vendor, "DEL");
+        assert_eq!(summary.product_code, 0xa0c5);
+        assert_eq!(summary.serial, 0x3030_4c4c);
+        assert_eq!(summary.monitor_name.as_deref(), Some("DELL U2720Q"));
+        assert_eq!(summary.serial_string.as_deref(), Some("CN0ABC123"));
+    }
+
+    #[test]
+    fn ignores_trailing_extension_blocks() {
+        let mut edid = decode_hex(DELL_U2720Q);
+        edid.extend_from_slice(&[0x02; EDID_BLOCK_SIZE]);
+        assert_eq!(parse_edid(&edid).unwrap().vendor, "DEL");
+    }
+
+    #[test]
+    fn thirteen_character_name_has_no_terminator() {
+        let mut edid = decode_hex(DELL_U2720Q);
+        edid[95..108].copy_from_slice(b"Odyssey G9 XL");
+        assert_eq!(
+            parse_edid(&edid).unwrap().monitor_name.as_deref(),
+            Some("Odyssey G9 XL")
+        );
+    }
+
+    #[test]
+    fn rejects_blobs_that_are_not_edid() {
+        let edid = decode_hex(DELL_U2720Q);
+        assert!(parse_edid(&[]).is_none());
+        assert!(parse_edid(&edid[..EDID_BLOCK_SIZE - 1]).is_none());
+        let mut bad_header = edid.clone();
+        bad_header[0] = 0xff;
+        assert!(parse_edid(&bad_header).is_none());
+    }
+
+    #[test]
+    fn capability_bits_map_to_names() {
+        assert!(capabilities_to_strings(0).is_empty());
+        assert_eq!(
+            capabilities_to_strings(0b1000_0110),
+            ["vrr", "rgb_range", "brightness"]
+        );
+        // Bits beyond the known capabilities are dropped
+        assert_eq!(capabilities_to_strings(1 << 12 | 1), ["overscan"]);
+    }
+}
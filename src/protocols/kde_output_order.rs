@@ -0,0 +1,74 @@
+use serde::Serialize;
+use wayland_client::{Connection, Dispatch, QueueHandle};
+use wayland_protocols_plasma::output_order::v1::client::kde_output_order_v1::{
+    self, KdeOutputOrderV1,
+};
+
+use crate::app::{AppData, UserData};
+
+// KDE output order info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KdeOutputOrderInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) outputs: Vec<String>,
+    #[serde(skip_serializing)]
+    pub(crate) pending_outputs: Vec<String>,
+}
+
+impl AppData {
+    pub(crate) fn add_kde_output_order(&mut self, name: u32) {
+        self.kde_output_orders.push(KdeOutputOrderInfo {
+            name,
+            outputs: Vec::new(),
+            pending_outputs: Vec::new(),
+        });
+    }
+
+    pub(crate) fn add_kde_output_order_output(&mut self, order_index: usize, output_name: String) {
+        if let Some(order) = self.kde_output_orders.get_mut(order_index) {
+            order.pending_outputs.push(output_name);
+        }
+    }
+
+    pub(crate) fn finish_kde_output_order(&mut self, order_index: usize) {
+        if let Some(order) = self.kde_output_orders.get_mut(order_index) {
+            order.outputs = std::mem::take(&mut order.pending_outputs);
+        }
+    }
+
+    /// Return the priority of an output in the KDE output order, 0 being the primary.
+    pub(crate) fn kde_output_priority(&self, output_name: &str) -> Option<usize> {
+        self.kde_output_orders
+            .first()?
+            .outputs
+            .iter()
+            .position(|name| name == output_name)
+    }
+}
+
+// Handle kde_output_order_v1 events
+impl Dispatch<KdeOutputOrderV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _order: &KdeOutputOrderV1,
+        event: kde_output_order_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::KdeOutputOrder { order_index } = data {
+            state.mark_event();
+            match event {
+                kde_output_order_v1::Event::Output { output_name } => {
+                    state.add_kde_output_order_output(*order_index, output_name);
+                }
+                kde_output_order_v1::Event::Done => {
+                    state.finish_kde_output_order(*order_index);
+                }
+                _ => {}
+            }
+        }
+    }
+}
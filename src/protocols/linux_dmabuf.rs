@@ -0,0 +1,371 @@
+use memmap2::MmapOptions;
+use serde::Serialize;
+use std::fs::File;
+use std::os::fd::OwnedFd;
+use wayland_client::{Connection, Dispatch, QueueHandle, WEnum};
+use wayland_protocols::wp::linux_dmabuf::zv1::client::{
+    zwp_linux_dmabuf_feedback_v1::{self, ZwpLinuxDmabufFeedbackV1},
+    zwp_linux_dmabuf_v1::{self, ZwpLinuxDmabufV1},
+};
+
+use crate::app::{AppData, UserData};
+use crate::drm_device::DrmDevice;
+use crate::protocols::wl_shm::format_to_fourcc;
+
+// Size of one format table entry: u32 format, u32 padding, u64 modifier
+const FORMAT_TABLE_ENTRY_SIZE: usize = 16;
+
+// Linux dmabuf info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DmabufInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) main_device: Option<DrmDevice>,
+    pub(crate) tranches: Vec<DmabufTranche>,
+    #[serde(skip_serializing)]
+    pub(crate) format_table: Vec<(u32, u64)>,
+    #[serde(skip_serializing)]
+    pub(crate) pending_tranche: DmabufTranche,
+}
+
+// Dmabuf feedback tranche info structure
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DmabufTranche {
+    pub(crate) target_device: Option<DrmDevice>,
+    pub(crate) scanout: bool,
+    pub(crate) formats: Vec<DmabufFormat>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DmabufFormat {
+    pub(crate) format: u32,
+    pub(crate) fourcc: String,
+    pub(crate) modifiers: Vec<String>,
+}
+
+impl DmabufTranche {
+    // Record a format without modifier information (pre-v3 format events)
+    pub(crate) fn add_format(&mut self, format: u32) {
+        if !self.formats.iter().any(|f| f.format == format) {
+            self.formats.push(DmabufFormat {
+                format,
+                fourcc: format_to_fourcc(format),
+                modifiers: Vec::new(),
+            });
+        }
+    }
+
+    // Group a format/modifier pair under its format, keeping first-seen order
+    pub(crate) fn add_format_modifier(&mut self, format: u32, modifier: u64) {
+        let modifier = modifier_to_string(modifier);
+        if let Some(entry) = self.formats.iter_mut().find(|f| f.format == format) {
+            if !entry.modifiers.contains(&modifier) {
+                entry.modifiers.push(modifier);
+            }
+        } else {
+            self.formats.push(DmabufFormat {
+                format,
+                fourcc: format_to_fourcc(format),
+                modifiers: vec![modifier],
+            });
+        }
+    }
+}
+
+impl AppData {
+    pub(crate) fn add_dmabuf(&mut self, name: u32) {
+        self.dmabuf_info.push(DmabufInfo {
+            name,
+            main_device: None,
+            tranches: Vec::new(),
+            format_table: Vec::new(),
+            pending_tranche: DmabufTranche::default(),
+        });
+    }
+
+    pub(crate) fn update_dmabuf_format_table(
+        &mut self,
+        dmabuf_index: usize,
+        fd: OwnedFd,
+        size: u32,
+    ) {
+        if let Some(dmabuf) = self.dmabuf_info.get_mut(dmabuf_index) {
+            dmabuf.format_table = read_format_table(fd, size as usize).unwrap_or_default();
+        }
+    }
+
+    pub(crate) fn update_dmabuf_main_device(&mut self, dmabuf_index: usize, device: &[u8]) {
+        let device = DrmDevice::from_dev_t_bytes(device, &self.sysfs_root);
+        if let Some(dmabuf) = self.dmabuf_info.get_mut(dmabuf_index) {
+            dmabuf.main_device = device;
+        }
+    }
+
+    pub(crate) fn update_dmabuf_tranche_target_device(
+        &mut self,
+        dmabuf_index: usize,
+        device: &[u8],
+    ) {
+        let device = DrmDevice::from_dev_t_bytes(device, &self.sysfs_root);
+        if let Some(dmabuf) = self.dmabuf_info.get_mut(dmabuf_index) {
+            dmabuf.pending_tranche.target_device = device;
+        }
+    }
+
+    pub(crate) fn update_dmabuf_tranche_flags(
+        &mut self,
+        dmabuf_index: usize,
+        flags: WEnum<zwp_linux_dmabuf_feedback_v1::TrancheFlags>,
+    ) {
+        if let Some(dmabuf) = self.dmabuf_info.get_mut(dmabuf_index) {
+            let flags_value = match flags {
+                WEnum::Value(v) => v.bits(),
+                WEnum::Unknown(v) => v,
+            };
+            dmabuf.pending_tranche.scanout =
+                flags_value & zwp_linux_dmabuf_feedback_v1::TrancheFlags::Scanout.bits() != 0;
+        }
+    }
+
+    pub(crate) fn update_dmabuf_tranche_formats(&mut self, dmabuf_index: usize, indices: &[u8]) {
+        if let Some(dmabuf) = self.dmabuf_info.get_mut(dmabuf_index) {
+            // Indices are native-endian u16 values into the format table
+            for chunk in indices.chunks_exact(2) {
+                let index = u16::from_ne_bytes([chunk[0], chunk[1]]) as usize;
+                if let Some(&(format, modifier)) = dmabuf.format_table.get(index) {
+                    dmabuf.pending_tranche.add_format_modifier(format, modifier);
+                }
+            }
+        }
+    }
+
+    // Legacy (version 3 and earlier) events all describe one implicit tranche
+    fn legacy_dmabuf_tranche(&mut self, dmabuf_index: usize) -> Option<&mut DmabufTranche> {
+        let dmabuf = self.dmabuf_info.get_mut(dmabuf_index)?;
+        if dmabuf.tranches.is_empty() {
+            dmabuf.tranches.push(DmabufTranche::default());
+        }
+        dmabuf.tranches.first_mut()
+    }
+
+    pub(crate) fn add_dmabuf_legacy_format(&mut self, dmabuf_index: usize, format: u32) {
+        if let Some(tranche) = self.legacy_dmabuf_tranche(dmabuf_index) {
+            tranche.add_format(format);
+        }
+    }
+
+    pub(crate) fn add_dmabuf_legacy_modifier(
+        &mut self,
+        dmabuf_index: usize,
+        format: u32,
+        modifier: u64,
+    ) {
+        if let Some(tranche) = self.legacy_dmabuf_tranche(dmabuf_index) {
+            tranche.add_format_modifier(format, modifier);
+        }
+    }
+
+    pub(crate) fn finish_dmabuf_tranche(&mut self, dmabuf_index: usize) {
+        if let Some(dmabuf) = self.dmabuf_info.get_mut(dmabuf_index) {
+            let tranche = std::mem::take(&mut dmabuf.pending_tranche);
+            dmabuf.tranches.push(tranche);
+        }
+    }
+}
+
+// Map the format table and decode its (format, modifier) pairs
+fn read_format_table(fd: OwnedFd, size: usize) -> Option<Vec<(u32, u64)>> {
+    if size == 0 {
+        return Some(Vec::new());
+    }
+    let file = File::from(fd);
+    // SAFETY: the protocol requires a private mapping, and the compositor never
+    // modifies the table once it has been sent.
+    let map = unsafe { MmapOptions::new().len(size).map_copy_read_only(&file) }.ok()?;
+    let table = map
+        .chunks_exact(FORMAT_TABLE_ENTRY_SIZE)
+        .map(|entry| {
+            let format = u32::from_ne_bytes(entry[0..4].try_into().unwrap());
+            let modifier = u64::from_ne_bytes(entry[8..16].try_into().unwrap());
+            (format, modifier)
+        })
+        .collect();
+    Some(table)
+}
+
+// Convert a DRM format modifier to a readable string
+pub(crate) fn modifier_to_string(modifier: u64) -> String {
+    match modifier {
+        0 => "linear".to_string(),
+        0x00ff_ffff_ffff_ffff => "invalid".to_string(),
+        _ => format!("0x{:016x}", modifier),
+    }
+}
+
+// Handle zwp_linux_dmabuf_v1 events
+impl Dispatch<ZwpLinuxDmabufV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _dmabuf: &ZwpLinuxDmabufV1,
+        event: zwp_linux_dmabuf_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // Version 4 and later report formats through zwp_linux_dmabuf_feedback_v1;
+        // these events are only sent to objects bound at version 3 or lower.
+        if let UserData::LinuxDmabuf { dmabuf_index } = data {
+            state.mark_event();
+            match event {
+                zwp_linux_dmabuf_v1::Event::Format { format } => {
+                    state.add_dmabuf_legacy_format(*dmabuf_index, format);
+                }
+                zwp_linux_dmabuf_v1::Event::Modifier {
+                    format,
+                    modifier_hi,
+                    modifier_lo,
+                } => {
+                    let modifier = ((modifier_hi as u64) << 32) | modifier_lo as u64;
+                    state.add_dmabuf_legacy_modifier(*dmabuf_index, format, modifier);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// Handle zwp_linux_dmabuf_feedback_v1 events
+impl Dispatch<ZwpLinuxDmabufFeedbackV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        feedback: &ZwpLinuxDmabufFeedbackV1,
+        event: zwp_linux_dmabuf_feedback_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::LinuxDmabuf { dmabuf_index } = data {
+            state.mark_event();
+            match event {
+                zwp_linux_dmabuf_feedback_v1::Event::FormatTable { fd, size } => {
+                    state.update_dmabuf_format_table(*dmabuf_index, fd, size);
+                }
+                zwp_linux_dmabuf_feedback_v1::Event::MainDevice { device } => {
+                    state.update_dmabuf_main_device(*dmabuf_index, &device);
+                }
+                zwp_linux_dmabuf_feedback_v1::Event::TrancheTargetDevice { device } => {
+                    state.update_dmabuf_tranche_target_device(*dmabuf_index, &device);
+                }
+                zwp_linux_dmabuf_feedback_v1::Event::TrancheFlags { flags } => {
+                    state.update_dmabuf_tranche_flags(*dmabuf_index, flags);
+                }
+                zwp_linux_dmabuf_feedback_v1::Event::TrancheFormats { indices } => {
+                    state.update_dmabuf_tranche_formats(*dmabuf_index, &indices);
+                }
+                zwp_linux_dmabuf_feedback_v1::Event::TrancheDone => {
+                    state.finish_dmabuf_tranche(*dmabuf_index);
+                }
+                zwp_linux_dmabuf_feedback_v1::Event::Done => {
+                    // Only the initial default feedback is reported.
+                    feedback.destroy();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const XRGB8888: u32 = 0x3432_5258;
+    const ARGB8888: u32 = 0x3432_5241;
+    const I915_Y_TILED: u64 = 0x0100_0000_0000_0002;
+
+    // Write a format table the way a compositor lays it out and hand over its fd
+    fn format_table_fd(name: &str, entries: &[(u32, u64)]) -> (OwnedFd, u32) {
+        let path = std::env::temp_dir().join(format!(
+            "wayland-info-format-table-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let mut file = File::create(&path).unwrap();
+        for &(format, modifier) in entries {
+            file.write_all(&format.to_ne_bytes()).unwrap();
+            file.write_all(&[0; 4]).unwrap();
+            file.write_all(&modifier.to_ne_bytes()).unwrap();
+        }
+        let file = File::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let size = (entries.len() * FORMAT_TABLE_ENTRY_SIZE) as u32;
+        (file.into(), size)
+    }
+
+    fn indices(indices: &[u16]) -> Vec<u8> {
+        indices.iter().flat_map(|i| i.to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn tranche_indices_resolve_through_the_format_table() {
+        let mut app_data = AppData::new();
+        app_data.add_dmabuf(1);
+        let (fd, size) = format_table_fd(
+            "tranches",
+            &[(XRGB8888, 0), (XRGB8888, I915_Y_TILED), (ARGB8888, 0)],
+        );
+        app_data.update_dmabuf_format_table(0, fd, size);
+        assert_eq!(app_data.dmabuf_info[0].format_table.len(), 3);
+
+        // Index 7 is past the end of the table and is skipped
+        app_data.update_dmabuf_tranche_formats(0, &indices(&[2, 0, 7, 1]));
+        app_data.finish_dmabuf_tranche(0);
+        app_data.update_dmabuf_tranche_formats(0, &indices(&[1]));
+        app_data.finish_dmabuf_tranche(0);
+
+        let tranches = &app_data.dmabuf_info[0].tranches;
+        assert_eq!(tranches.len(), 2);
+        let first: Vec<_> = tranches[0]
+            .formats
+            .iter()
+            .map(|f| (f.fourcc.as_str(), f.modifiers.clone()))
+            .collect();
+        assert_eq!(
+            first,
+            [
+                ("AR24", vec!["linear".to_string()]),
+                (
+                    "XR24",
+                    vec!["linear".to_string(), "0x0100000000000002".to_string()]
+                ),
+            ]
+        );
+        assert_eq!(tranches[1].formats.len(), 1);
+        assert_eq!(tranches[1].formats[0].modifiers, ["0x0100000000000002"]);
+    }
+
+    #[test]
+    fn empty_format_table_leaves_tranches_empty() {
+        let mut app_data = AppData::new();
+        app_data.add_dmabuf(1);
+        let (fd, size) = format_table_fd("empty", &[]);
+        app_data.update_dmabuf_format_table(0, fd, size);
+        app_data.update_dmabuf_tranche_formats(0, &indices(&[0, 1]));
+        app_data.finish_dmabuf_tranche(0);
+        assert!(app_data.dmabuf_info[0].tranches[0].formats.is_empty());
+    }
+
+    #[test]
+    fn repeated_format_modifier_pairs_are_recorded_once() {
+        let mut tranche = DmabufTranche::default();
+        tranche.add_format_modifier(XRGB8888, 0);
+        tranche.add_format_modifier(XRGB8888, 0x00ff_ffff_ffff_ffff);
+        tranche.add_format_modifier(XRGB8888, 0);
+        assert_eq!(tranche.formats.len(), 1);
+        assert_eq!(tranche.formats[0].modifiers, ["linear", "invalid"]);
+    }
+}
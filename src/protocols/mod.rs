@@ -1,8 +1,24 @@
+pub mod ext_foreign_toplevel_list;
+pub mod ext_image_copy_capture;
+pub mod ext_workspace;
+pub mod kde_output_device;
+pub mod kde_output_order;
+pub mod linux_dmabuf;
+pub mod plasma_virtual_desktop;
+pub mod plasma_window_management;
 pub mod registry;
 pub mod treeland_output_manager;
+pub mod wl_drm;
 pub mod wl_output;
 pub mod wl_seat;
 pub mod wl_shm;
+pub mod wlr_export_dmabuf;
+pub mod wlr_foreign_toplevel;
+pub mod wlr_output_management;
+pub mod wlr_screencopy;
+pub mod wp_color_management;
+pub mod wp_color_representation;
 pub mod wp_drm_lease_device;
 pub mod wp_presentation;
+pub mod wp_tablet;
 pub mod xdg_output;
@@ -0,0 +1,172 @@
+use serde::Serialize;
+use wayland_client::{Connection, Dispatch, QueueHandle};
+use wayland_protocols_plasma::plasma_virtual_desktop::client::{
+    org_kde_plasma_virtual_desktop::{self, OrgKdePlasmaVirtualDesktop},
+    org_kde_plasma_virtual_desktop_management::{self, OrgKdePlasmaVirtualDesktopManagement},
+};
+
+use crate::app::{AppData, UserData};
+
+// Plasma virtual desktop management info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlasmaVirtualDesktopManagementInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) rows: Option<u32>,
+    pub(crate) desktops: Vec<PlasmaVirtualDesktopInfo>,
+}
+
+// Plasma virtual desktop info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlasmaVirtualDesktopInfo {
+    #[serde(skip_serializing)]
+    pub(crate) desktop: OrgKdePlasmaVirtualDesktop,
+    pub(crate) id: String,
+    pub(crate) name: String,
+    pub(crate) position: u32,
+    pub(crate) active: bool,
+}
+
+impl AppData {
+    pub(crate) fn add_plasma_virtual_desktop_management(&mut self, name: u32) {
+        self.plasma_virtual_desktop_managers
+            .push(PlasmaVirtualDesktopManagementInfo {
+                name,
+                rows: None,
+                desktops: Vec::new(),
+            });
+    }
+
+    pub(crate) fn add_plasma_virtual_desktop(
+        &mut self,
+        manager_index: usize,
+        desktop: OrgKdePlasmaVirtualDesktop,
+        id: String,
+        position: u32,
+    ) {
+        if let Some(manager) = self.plasma_virtual_desktop_managers.get_mut(manager_index) {
+            // Positions are indices into the desktop list, so later desktops shift
+            let position = position.min(manager.desktops.len() as u32);
+            for existing in &mut manager.desktops {
+                if existing.position >= position {
+                    existing.position += 1;
+                }
+            }
+            manager.desktops.insert(
+                position as usize,
+                PlasmaVirtualDesktopInfo {
+                    desktop,
+                    id,
+                    name: String::new(),
+                    position,
+                    active: false,
+                },
+            );
+        }
+    }
+
+    pub(crate) fn remove_plasma_virtual_desktop(&mut self, manager_index: usize, id: &str) {
+        if let Some(manager) = self.plasma_virtual_desktop_managers.get_mut(manager_index) {
+            manager.desktops.retain(|desktop| desktop.id != id);
+            for (position, desktop) in manager.desktops.iter_mut().enumerate() {
+                desktop.position = position as u32;
+            }
+        }
+    }
+
+    /// Return the name of a virtual desktop by id, for labelling window membership.
+    pub(crate) fn plasma_virtual_desktop_name(&self, id: &str) -> Option<&str> {
+        self.plasma_virtual_desktop_managers
+            .iter()
+            .flat_map(|manager| manager.desktops.iter())
+            .find(|desktop| desktop.id == id)
+            .map(|desktop| desktop.name.as_str())
+    }
+
+    fn plasma_virtual_desktop_mut(
+        &mut self,
+        desktop: &OrgKdePlasmaVirtualDesktop,
+    ) -> Option<&mut PlasmaVirtualDesktopInfo> {
+        self.plasma_virtual_desktop_managers
+            .iter_mut()
+            .flat_map(|manager| manager.desktops.iter_mut())
+            .find(|info| &info.desktop == desktop)
+    }
+}
+
+// Handle org_kde_plasma_virtual_desktop_management events
+impl Dispatch<OrgKdePlasmaVirtualDesktopManagement, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        manager: &OrgKdePlasmaVirtualDesktopManagement,
+        event: org_kde_plasma_virtual_desktop_management::Event,
+        data: &UserData,
+        _conn: &Connection,
+        qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::PlasmaVirtualDesktopManagement { manager_index } = data {
+            state.mark_event();
+            match event {
+                org_kde_plasma_virtual_desktop_management::Event::DesktopCreated {
+                    desktop_id,
+                    position,
+                } => {
+                    let desktop = manager.get_virtual_desktop(
+                        desktop_id.clone(),
+                        qh,
+                        UserData::PlasmaVirtualDesktop,
+                    );
+                    state.add_plasma_virtual_desktop(*manager_index, desktop, desktop_id, position);
+                }
+                org_kde_plasma_virtual_desktop_management::Event::DesktopRemoved { desktop_id } => {
+                    state.remove_plasma_virtual_desktop(*manager_index, &desktop_id);
+                }
+                org_kde_plasma_virtual_desktop_management::Event::Rows { rows } => {
+                    if let Some(info) = state
+                        .plasma_virtual_desktop_managers
+                        .get_mut(*manager_index)
+                    {
+                        info.rows = Some(rows);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// Handle org_kde_plasma_virtual_desktop events
+impl Dispatch<OrgKdePlasmaVirtualDesktop, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        desktop: &OrgKdePlasmaVirtualDesktop,
+        event: org_kde_plasma_virtual_desktop::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::PlasmaVirtualDesktop = data {
+            state.mark_event();
+            let Some(info) = state.plasma_virtual_desktop_mut(desktop) else {
+                return;
+            };
+            match event {
+                org_kde_plasma_virtual_desktop::Event::DesktopId { desktop_id } => {
+                    info.id = desktop_id;
+                }
+                org_kde_plasma_virtual_desktop::Event::Name { name } => {
+                    info.name = name;
+                }
+                org_kde_plasma_virtual_desktop::Event::Activated => {
+                    info.active = true;
+                }
+                org_kde_plasma_virtual_desktop::Event::Deactivated => {
+                    info.active = false;
+                }
+                _ => {}
+            }
+        }
+    }
+}
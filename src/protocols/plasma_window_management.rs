@@ -0,0 +1,258 @@
+use serde::Serialize;
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle};
+use wayland_protocols_plasma::plasma_window_management::client::{
+    org_kde_plasma_window::{self, OrgKdePlasmaWindow},
+    org_kde_plasma_window_management::{self, OrgKdePlasmaWindowManagement},
+};
+
+use crate::app::{AppData, UserData};
+
+// Plasma window management info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlasmaWindowManagementInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) show_desktop: bool,
+    pub(crate) windows: Vec<PlasmaWindowInfo>,
+}
+
+// Plasma window info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlasmaWindowInfo {
+    #[serde(skip_serializing)]
+    pub(crate) window: OrgKdePlasmaWindow,
+    pub(crate) uuid: String,
+    pub(crate) title: String,
+    pub(crate) app_id: String,
+    pub(crate) pid: Option<u32>,
+    pub(crate) geometry: Option<PlasmaWindowGeometry>,
+    pub(crate) virtual_desktops: Vec<String>,
+    pub(crate) state: Vec<String>,
+    pub(crate) parent: Option<String>,
+}
+
+// Plasma window geometry in absolute compositor coordinates
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlasmaWindowGeometry {
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+impl AppData {
+    pub(crate) fn add_plasma_window_management(&mut self, name: u32) {
+        self.plasma_window_managers
+            .push(PlasmaWindowManagementInfo {
+                name,
+                show_desktop: false,
+                windows: Vec::new(),
+            });
+    }
+
+    pub(crate) fn add_plasma_window(
+        &mut self,
+        manager_index: usize,
+        window: OrgKdePlasmaWindow,
+        uuid: String,
+    ) {
+        if let Some(manager) = self.plasma_window_managers.get_mut(manager_index) {
+            manager.windows.push(PlasmaWindowInfo {
+                window,
+                uuid,
+                title: String::new(),
+                app_id: String::new(),
+                pid: None,
+                geometry: None,
+                virtual_desktops: Vec::new(),
+                state: Vec::new(),
+                parent: None,
+            });
+        }
+    }
+
+    fn plasma_window_mut(&mut self, window: &OrgKdePlasmaWindow) -> Option<&mut PlasmaWindowInfo> {
+        self.plasma_window_managers
+            .iter_mut()
+            .flat_map(|manager| manager.windows.iter_mut())
+            .find(|info| &info.window == window)
+    }
+
+    fn plasma_window_uuid(&self, window: &OrgKdePlasmaWindow) -> Option<String> {
+        self.plasma_window_managers
+            .iter()
+            .flat_map(|manager| manager.windows.iter())
+            .find(|info| &info.window == window)
+            .map(|info| info.uuid.clone())
+    }
+}
+
+fn state_to_strings(flags: u32) -> Vec<String> {
+    const NAMES: [&str; 19] = [
+        "active",
+        "minimized",
+        "maximized",
+        "fullscreen",
+        "keep_above",
+        "keep_below",
+        "on_all_desktops",
+        "demands_attention",
+        "closeable",
+        "minimizable",
+        "maximizable",
+        "fullscreenable",
+        "skiptaskbar",
+        "shadeable",
+        "shaded",
+        "movable",
+        "resizable",
+        "virtual_desktop_changeable",
+        "skipswitcher",
+    ];
+    NAMES
+        .iter()
+        .enumerate()
+        .filter(|(bit, _)| flags & (1 << bit) != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+// Handle org_kde_plasma_window_management events
+impl Dispatch<OrgKdePlasmaWindowManagement, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        manager: &OrgKdePlasmaWindowManagement,
+        event: org_kde_plasma_window_management::Event,
+        data: &UserData,
+        _conn: &Connection,
+        qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::PlasmaWindowManagement { manager_index } = data {
+            state.mark_event();
+            match event {
+                org_kde_plasma_window_management::Event::ShowDesktopChanged { state: enabled } => {
+                    if let Some(info) = state.plasma_window_managers.get_mut(*manager_index) {
+                        info.show_desktop = enabled != 0;
+                    }
+                }
+                // Version 13 and later announce windows with window_with_uuid instead
+                org_kde_plasma_window_management::Event::Window { id }
+                    if manager.version() < 13 =>
+                {
+                    let window = manager.get_window(id, qh, UserData::PlasmaWindow);
+                    state.add_plasma_window(*manager_index, window, id.to_string());
+                }
+                org_kde_plasma_window_management::Event::WindowWithUuid { uuid, .. } => {
+                    let window =
+                        manager.get_window_by_uuid(uuid.clone(), qh, UserData::PlasmaWindow);
+                    state.add_plasma_window(*manager_index, window, uuid);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// Handle org_kde_plasma_window events
+impl Dispatch<OrgKdePlasmaWindow, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        window: &OrgKdePlasmaWindow,
+        event: org_kde_plasma_window::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::PlasmaWindow = data {
+            state.mark_event();
+            if let org_kde_plasma_window::Event::Unmapped = event {
+                for manager in &mut state.plasma_window_managers {
+                    manager.windows.retain(|info| &info.window != window);
+                }
+                if window.version() >= 4 {
+                    window.destroy();
+                }
+                return;
+            }
+            let parent = match &event {
+                org_kde_plasma_window::Event::ParentWindow { parent } => {
+                    parent.as_ref().and_then(|p| state.plasma_window_uuid(p))
+                }
+                _ => None,
+            };
+            let Some(info) = state.plasma_window_mut(window) else {
+                return;
+            };
+            match event {
+                org_kde_plasma_window::Event::TitleChanged { title } => {
+                    info.title = title;
+                }
+                org_kde_plasma_window::Event::AppIdChanged { app_id } => {
+                    info.app_id = app_id;
+                }
+                org_kde_plasma_window::Event::PidChanged { pid } => {
+                    info.pid = Some(pid);
+                }
+                org_kde_plasma_window::Event::Geometry {
+                    x,
+                    y,
+                    width,
+                    height,
+                } => {
+                    info.geometry = Some(PlasmaWindowGeometry {
+                        x,
+                        y,
+                        width,
+                        height,
+                    });
+                }
+                org_kde_plasma_window::Event::StateChanged { flags } => {
+                    info.state = state_to_strings(flags);
+                }
+                org_kde_plasma_window::Event::VirtualDesktopEntered { id }
+                    if !info.virtual_desktops.contains(&id) =>
+                {
+                    info.virtual_desktops.push(id);
+                }
+                org_kde_plasma_window::Event::VirtualDesktopLeft { is } => {
+                    info.virtual_desktops.retain(|id| id != &is);
+                }
+                org_kde_plasma_window::Event::ParentWindow { .. } => {
+                    info.parent = parent;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use org_kde_plasma_window_management::State;
+
+    #[test]
+    fn state_names_match_protocol_bits() {
+        let flags = State::Active as u32
+            | State::Maximized as u32
+            | State::Closeable as u32
+            | State::Skipswitcher as u32;
+        assert_eq!(
+            state_to_strings(flags),
+            ["active", "maximized", "closeable", "skipswitcher"]
+        );
+        assert_eq!(
+            state_to_strings(State::VirtualDesktopChangeable as u32),
+            ["virtual_desktop_changeable"]
+        );
+    }
+
+    #[test]
+    fn unnamed_state_bits_are_dropped() {
+        assert!(state_to_strings(0).is_empty());
+        assert_eq!(state_to_strings(1 << 19 | State::Shaded as u32), ["shaded"]);
+    }
+}
@@ -1,12 +1,33 @@
 use wayland_client::{
     protocol::{wl_output::WlOutput, wl_registry, wl_seat::WlSeat, wl_shm::WlShm},
-    Connection, Dispatch, QueueHandle,
+    Connection, Dispatch, Proxy, QueueHandle,
 };
+use wayland_protocols::ext::foreign_toplevel_list::v1::client::ext_foreign_toplevel_list_v1::ExtForeignToplevelListV1;
+use wayland_protocols::ext::image_capture_source::v1::client::{
+    ext_foreign_toplevel_image_capture_source_manager_v1::ExtForeignToplevelImageCaptureSourceManagerV1,
+    ext_output_image_capture_source_manager_v1::ExtOutputImageCaptureSourceManagerV1,
+};
+use wayland_protocols::ext::image_copy_capture::v1::client::ext_image_copy_capture_manager_v1::ExtImageCopyCaptureManagerV1;
+use wayland_protocols::ext::workspace::v1::client::ext_workspace_manager_v1::ExtWorkspaceManagerV1;
+use wayland_protocols::wp::color_management::v1::client::wp_color_manager_v1::WpColorManagerV1;
+use wayland_protocols::wp::color_representation::v1::client::wp_color_representation_manager_v1::WpColorRepresentationManagerV1;
+use wayland_protocols::wp::drm_lease::v1::client::wp_drm_lease_device_v1::WpDrmLeaseDeviceV1;
+use wayland_protocols::wp::linux_dmabuf::zv1::client::zwp_linux_dmabuf_v1::ZwpLinuxDmabufV1;
 use wayland_protocols::wp::presentation_time::client::wp_presentation::WpPresentation;
+use wayland_protocols::wp::tablet::zv2::client::zwp_tablet_manager_v2::ZwpTabletManagerV2;
 use wayland_protocols::xdg::xdg_output::zv1::client::zxdg_output_manager_v1::ZxdgOutputManagerV1;
+use wayland_protocols_plasma::output_device::v2::client::kde_output_device_v2::KdeOutputDeviceV2;
+use wayland_protocols_plasma::output_order::v1::client::kde_output_order_v1::KdeOutputOrderV1;
+use wayland_protocols_plasma::plasma_virtual_desktop::client::org_kde_plasma_virtual_desktop_management::OrgKdePlasmaVirtualDesktopManagement;
+use wayland_protocols_plasma::plasma_window_management::client::org_kde_plasma_window_management::OrgKdePlasmaWindowManagement;
 use wayland_protocols_treeland::output_manager::v1::client::treeland_output_manager_v1::TreelandOutputManagerV1;
+use wayland_protocols_wlr::export_dmabuf::v1::client::zwlr_export_dmabuf_manager_v1::ZwlrExportDmabufManagerV1;
+use wayland_protocols_wlr::foreign_toplevel::v1::client::zwlr_foreign_toplevel_manager_v1::ZwlrForeignToplevelManagerV1;
+use wayland_protocols_wlr::output_management::v1::client::zwlr_output_manager_v1::ZwlrOutputManagerV1;
+use wayland_protocols_wlr::screencopy::v1::client::zwlr_screencopy_manager_v1::ZwlrScreencopyManagerV1;
 
 use crate::app::{AppData, UserData};
+use crate::protocols::wl_drm::client::wl_drm::WlDrm;
 
 // Handle wl_registry events
 impl Dispatch<wl_registry::WlRegistry, ()> for AppData {
@@ -41,14 +62,199 @@ impl Dispatch<wl_registry::WlRegistry, ()> for AppData {
                     UserData::Output { output_index },
                 );
                 state.output_objects.push(output);
+            } else if interface == "zwlr_output_manager_v1" {
+                state.add_wlr_output_manager(name);
+                let manager_index = state.wlr_output_managers.len() - 1;
+                let manager = registry.bind::<ZwlrOutputManagerV1, _, _>(
+                    name,
+                    version.min(4),
+                    qh,
+                    UserData::WlrOutputManager { manager_index },
+                );
+                state.wlr_output_manager_objects.push(manager);
+            } else if interface == "kde_output_device_v2" {
+                state.add_kde_output_device(name);
+                let device_index = state.kde_output_devices.len() - 1;
+                let device = registry.bind::<KdeOutputDeviceV2, _, _>(
+                    name,
+                    version.min(11),
+                    qh,
+                    UserData::KdeOutputDevice { device_index },
+                );
+                state.kde_output_device_objects.push(device);
+            } else if interface == "kde_output_order_v1" {
+                state.add_kde_output_order(name);
+                let order_index = state.kde_output_orders.len() - 1;
+                let order = registry.bind::<KdeOutputOrderV1, _, _>(
+                    name,
+                    version.min(1),
+                    qh,
+                    UserData::KdeOutputOrder { order_index },
+                );
+                state.kde_output_order_objects.push(order);
+            } else if interface == "ext_workspace_manager_v1" {
+                state.add_workspace_manager(name);
+                let manager_index = state.workspace_managers.len() - 1;
+                let manager = registry.bind::<ExtWorkspaceManagerV1, _, _>(
+                    name,
+                    version.min(1),
+                    qh,
+                    UserData::WorkspaceManager { manager_index },
+                );
+                state.workspace_manager_objects.push(manager);
+            } else if interface == "ext_foreign_toplevel_list_v1" {
+                state.add_foreign_toplevel_list(name);
+                let list_index = state.foreign_toplevel_lists.len() - 1;
+                let list = registry.bind::<ExtForeignToplevelListV1, _, _>(
+                    name,
+                    version.min(1),
+                    qh,
+                    UserData::ForeignToplevelList { list_index },
+                );
+                state.foreign_toplevel_list_objects.push(list);
+            } else if interface == "zwlr_foreign_toplevel_manager_v1" {
+                state.add_wlr_toplevel_manager(name);
+                let manager_index = state.wlr_toplevel_managers.len() - 1;
+                let manager = registry.bind::<ZwlrForeignToplevelManagerV1, _, _>(
+                    name,
+                    version.min(3),
+                    qh,
+                    UserData::WlrToplevelManager { manager_index },
+                );
+                state.wlr_toplevel_manager_objects.push(manager);
+            } else if interface == "org_kde_plasma_window_management" {
+                state.add_plasma_window_management(name);
+                let manager_index = state.plasma_window_managers.len() - 1;
+                let manager = registry.bind::<OrgKdePlasmaWindowManagement, _, _>(
+                    name,
+                    version.min(16),
+                    qh,
+                    UserData::PlasmaWindowManagement { manager_index },
+                );
+                state.plasma_window_manager_objects.push(manager);
+            } else if interface == "org_kde_plasma_virtual_desktop_management" {
+                state.add_plasma_virtual_desktop_management(name);
+                let manager_index = state.plasma_virtual_desktop_managers.len() - 1;
+                let manager = registry.bind::<OrgKdePlasmaVirtualDesktopManagement, _, _>(
+                    name,
+                    version.min(2),
+                    qh,
+                    UserData::PlasmaVirtualDesktopManagement { manager_index },
+                );
+                state.plasma_virtual_desktop_manager_objects.push(manager);
+            } else if interface == "zwp_tablet_manager_v2" {
+                state.add_tablet_manager(name);
+                let manager_index = state.tablet_managers.len() - 1;
+                let manager = registry.bind::<ZwpTabletManagerV2, _, _>(
+                    name,
+                    version.min(2),
+                    qh,
+                    UserData::TabletManager { manager_index },
+                );
+                state.tablet_manager_objects.push(manager);
+            } else if interface == "wp_color_manager_v1" {
+                state.add_color_manager(name);
+                let manager_index = state.color_managers.len() - 1;
+                let manager = registry.bind::<WpColorManagerV1, _, _>(
+                    name,
+                    version.min(2),
+                    qh,
+                    UserData::ColorManager { manager_index },
+                );
+                state.color_manager_objects.push(manager);
+            } else if interface == "wp_color_representation_manager_v1" {
+                state.add_color_representation(name);
+                let representation_index = state.color_representation_info.len() - 1;
+                let manager = registry.bind::<WpColorRepresentationManagerV1, _, _>(
+                    name,
+                    version.min(1),
+                    qh,
+                    UserData::ColorRepresentation {
+                        representation_index,
+                    },
+                );
+                state.color_representation_objects.push(manager);
+            } else if interface == "ext_output_image_capture_source_manager_v1" {
+                let manager = registry.bind::<ExtOutputImageCaptureSourceManagerV1, _, _>(
+                    name,
+                    version.min(1),
+                    qh,
+                    UserData::ImageCaptureSourceManager,
+                );
+                state.output_capture_source_manager_objects.push(manager);
+            } else if interface == "ext_foreign_toplevel_image_capture_source_manager_v1" {
+                let manager = registry.bind::<ExtForeignToplevelImageCaptureSourceManagerV1, _, _>(
+                    name,
+                    version.min(1),
+                    qh,
+                    UserData::ImageCaptureSourceManager,
+                );
+                state.toplevel_capture_source_manager_objects.push(manager);
+            } else if interface == "ext_image_copy_capture_manager_v1" {
+                let manager = registry.bind::<ExtImageCopyCaptureManagerV1, _, _>(
+                    name,
+                    version.min(1),
+                    qh,
+                    UserData::ImageCopyCaptureManager,
+                );
+                state.image_copy_capture_manager_objects.push(manager);
+            } else if interface == "zwlr_screencopy_manager_v1" {
+                state.add_screencopy_manager(name);
+                let manager_index = state.screencopy_managers.len() - 1;
+                let manager = registry.bind::<ZwlrScreencopyManagerV1, _, _>(
+                    name,
+                    version.min(3),
+                    qh,
+                    UserData::ScreencopyManager { manager_index },
+                );
+                state.screencopy_manager_objects.push(manager);
+            } else if interface == "zwlr_export_dmabuf_manager_v1" {
+                let manager = registry.bind::<ZwlrExportDmabufManagerV1, _, _>(
+                    name,
+                    version.min(1),
+                    qh,
+                    UserData::ExportDmabufManager,
+                );
+                state.export_dmabuf_manager_objects.push(manager);
             } else if interface == "wl_shm" {
                 state.add_shm(name);
                 let shm_index = state.shm_info.len() - 1;
                 let shm =
                     registry.bind::<WlShm, _, _>(name, version, qh, UserData::Shm { shm_index });
                 state.shm_objects.push(shm);
+            } else if interface == "zwp_linux_dmabuf_v1" {
+                state.add_dmabuf(name);
+                let dmabuf_index = state.dmabuf_info.len() - 1;
+                let dmabuf = registry.bind::<ZwpLinuxDmabufV1, _, _>(
+                    name,
+                    version.min(4),
+                    qh,
+                    UserData::LinuxDmabuf { dmabuf_index },
+                );
+                if dmabuf.version() >= 4 {
+                    dmabuf.get_default_feedback(qh, UserData::LinuxDmabuf { dmabuf_index });
+                }
+                state.dmabuf_objects.push(dmabuf);
+            } else if interface == "wl_drm" {
+                state.add_wl_drm(name);
+                let drm_index = state.wl_drm_info.len() - 1;
+                let drm = registry.bind::<WlDrm, _, _>(
+                    name,
+                    version.min(2),
+                    qh,
+                    UserData::WlDrm { drm_index },
+                );
+                state.wl_drm_objects.push(drm);
             } else if interface == "wp_drm_lease_device_v1" {
                 state.add_drm_lease_device(name);
+                let device_index = state.drm_lease_devices.len() - 1;
+                let device = registry.bind::<WpDrmLeaseDeviceV1, _, _>(
+                    name,
+                    version.min(1),
+                    qh,
+                    UserData::DrmLeaseDevice { device_index },
+                );
+                state.drm_lease_device_objects.push(device);
             } else if interface == "wp_presentation" {
                 state.add_presentation(name);
                 let presentation_index = state.presentation_info.len() - 1;
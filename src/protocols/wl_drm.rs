@@ -0,0 +1,117 @@
+use serde::Serialize;
+use std::os::unix::fs::MetadataExt;
+use wayland_client::{Connection, Dispatch, QueueHandle};
+
+use crate::app::{AppData, UserData};
+use crate::drm_device::DrmDevice;
+use crate::protocols::wl_shm::{format_to_fourcc, ShmFormat};
+
+// wl_drm is a Mesa protocol that is not shipped by wayland-protocols
+pub mod client {
+    use wayland_client;
+    use wayland_client::protocol::*;
+
+    pub mod __interfaces {
+        use wayland_client::backend as wayland_backend;
+        use wayland_client::protocol::__interfaces::*;
+        wayland_scanner::generate_interfaces!("protocols/wayland-drm.xml");
+    }
+    use self::__interfaces::*;
+
+    wayland_scanner::generate_client_code!("protocols/wayland-drm.xml");
+}
+
+use client::wl_drm::{self, WlDrm};
+
+// wl_drm info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WlDrmInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) device_path: Option<String>,
+    pub(crate) device: Option<DrmDevice>,
+    pub(crate) formats: Vec<ShmFormat>,
+    pub(crate) capabilities: Vec<String>,
+    // Authentication is never requested: a rejected magic is a fatal protocol
+    // error, so this only records an unsolicited authenticated event
+    pub(crate) authenticated: bool,
+}
+
+impl AppData {
+    pub(crate) fn add_wl_drm(&mut self, name: u32) {
+        self.wl_drm_info.push(WlDrmInfo {
+            name,
+            device_path: None,
+            device: None,
+            formats: Vec::new(),
+            capabilities: Vec::new(),
+            authenticated: false,
+        });
+    }
+
+    pub(crate) fn update_wl_drm_device(&mut self, drm_index: usize, path: String) {
+        let device = std::fs::metadata(&path)
+            .ok()
+            .map(|metadata| DrmDevice::from_dev_t(metadata.rdev(), &self.sysfs_root));
+        if let Some(drm) = self.wl_drm_info.get_mut(drm_index) {
+            drm.device_path = Some(path);
+            drm.device = device;
+        }
+    }
+
+    pub(crate) fn add_wl_drm_format(&mut self, drm_index: usize, format: u32) {
+        if let Some(drm) = self.wl_drm_info.get_mut(drm_index) {
+            drm.formats.push(ShmFormat {
+                format,
+                fourcc: format_to_fourcc(format),
+            });
+        }
+    }
+
+    pub(crate) fn update_wl_drm_capabilities(&mut self, drm_index: usize, value: u32) {
+        if let Some(drm) = self.wl_drm_info.get_mut(drm_index) {
+            let mut caps = Vec::new();
+            if value & wl_drm::Capability::Prime as u32 != 0 {
+                caps.push("prime".to_string());
+            }
+            drm.capabilities = caps;
+        }
+    }
+
+    pub(crate) fn update_wl_drm_authenticated(&mut self, drm_index: usize) {
+        if let Some(drm) = self.wl_drm_info.get_mut(drm_index) {
+            drm.authenticated = true;
+        }
+    }
+}
+
+// Handle wl_drm events
+impl Dispatch<WlDrm, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _drm: &WlDrm,
+        event: wl_drm::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::WlDrm { drm_index } = data {
+            state.mark_event();
+            match event {
+                wl_drm::Event::Device { name } => {
+                    state.update_wl_drm_device(*drm_index, name);
+                }
+                wl_drm::Event::Format { format } => {
+                    state.add_wl_drm_format(*drm_index, format);
+                }
+                wl_drm::Event::Capabilities { value } => {
+                    state.update_wl_drm_capabilities(*drm_index, value);
+                }
+                wl_drm::Event::Authenticated => {
+                    state.update_wl_drm_authenticated(*drm_index);
+                }
+            }
+        }
+    }
+}
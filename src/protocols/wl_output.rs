@@ -5,6 +5,9 @@ use wayland_client::{
 };
 
 use crate::app::{AppData, UserData};
+use crate::protocols::ext_image_copy_capture::CaptureConstraintsInfo;
+use crate::protocols::wlr_export_dmabuf::ExportDmabufFrameInfo;
+use crate::protocols::wp_color_management::ImageDescriptionInfo;
 
 // Output info structure
 #[derive(Debug, Clone, Serialize)]
@@ -24,6 +27,9 @@ pub struct OutputInfo {
     pub(crate) subpixel_orientation: String,
     pub(crate) output_transform: String,
     pub(crate) modes: Vec<OutputMode>,
+    pub(crate) image_description: Option<ImageDescriptionInfo>,
+    pub(crate) capture_constraints: Option<CaptureConstraintsInfo>,
+    pub(crate) export_dmabuf_frame: Option<ExportDmabufFrameInfo>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -64,6 +70,9 @@ impl AppData {
             subpixel_orientation: String::new(),
             output_transform: String::new(),
             modes: Vec::new(),
+            image_description: None,
+            capture_constraints: None,
+            export_dmabuf_frame: None,
         });
     }
 
@@ -76,27 +85,9 @@ impl AppData {
             output.make = geometry.make;
             output.model = geometry.model;
 
-            output.subpixel_orientation = match geometry.subpixel {
-                WEnum::Value(wl_output::Subpixel::Unknown) => "unknown".to_string(),
-                WEnum::Value(wl_output::Subpixel::None) => "none".to_string(),
-                WEnum::Value(wl_output::Subpixel::HorizontalRgb) => "horizontal_rgb".to_string(),
-                WEnum::Value(wl_output::Subpixel::HorizontalBgr) => "horizontal_bgr".to_string(),
-                WEnum::Value(wl_output::Subpixel::VerticalRgb) => "vertical_rgb".to_string(),
-                WEnum::Value(wl_output::Subpixel::VerticalBgr) => "vertical_bgr".to_string(),
-                _ => "unknown".to_string(),
-            };
-
-            output.output_transform = match geometry.transform {
-                WEnum::Value(wl_output::Transform::Normal) => "normal".to_string(),
-                WEnum::Value(wl_output::Transform::_90) => "90".to_string(),
-                WEnum::Value(wl_output::Transform::_180) => "180".to_string(),
-                WEnum::Value(wl_output::Transform::_270) => "270".to_string(),
-                WEnum::Value(wl_output::Transform::Flipped) => "flipped".to_string(),
-                WEnum::Value(wl_output::Transform::Flipped90) => "flipped-90".to_string(),
-                WEnum::Value(wl_output::Transform::Flipped180) => "flipped-180".to_string(),
-                WEnum::Value(wl_output::Transform::Flipped270) => "flipped-270".to_string(),
-                _ => "normal".to_string(),
-            };
+            output.subpixel_orientation = subpixel_to_string(geometry.subpixel);
+
+            output.output_transform = transform_to_string(geometry.transform);
         }
     }
 
@@ -144,6 +135,55 @@ impl AppData {
             output.description = description;
         }
     }
+
+    /// Return the `OutputInfo` name of a bound `wl_output` object.
+    pub(crate) fn output_name(&self, output: &WlOutput) -> Option<String> {
+        let index = self.output_objects.iter().position(|o| o == output)?;
+        self.outputs.get(index).map(|info| info.output_name.clone())
+    }
+}
+
+// Convert a wl_output subpixel layout to its display name
+pub(crate) fn subpixel_to_string(subpixel: WEnum<wl_output::Subpixel>) -> String {
+    match subpixel {
+        WEnum::Value(wl_output::Subpixel::Unknown) => "unknown".to_string(),
+        WEnum::Value(wl_output::Subpixel::None) => "none".to_string(),
+        WEnum::Value(wl_output::Subpixel::HorizontalRgb) => "horizontal_rgb".to_string(),
+        WEnum::Value(wl_output::Subpixel::HorizontalBgr) => "horizontal_bgr".to_string(),
+        WEnum::Value(wl_output::Subpixel::VerticalRgb) => "vertical_rgb".to_string(),
+        WEnum::Value(wl_output::Subpixel::VerticalBgr) => "vertical_bgr".to_string(),
+        _ => "unknown".to_string(),
+    }
+}
+
+// Convert a wl_output transform to its display name
+pub(crate) fn transform_to_string(transform: WEnum<wl_output::Transform>) -> String {
+    match transform {
+        WEnum::Value(wl_output::Transform::Normal) => "normal".to_string(),
+        WEnum::Value(wl_output::Transform::_90) => "90".to_string(),
+        WEnum::Value(wl_output::Transform::_180) => "180".to_string(),
+        WEnum::Value(wl_output::Transform::_270) => "270".to_string(),
+        WEnum::Value(wl_output::Transform::Flipped) => "flipped".to_string(),
+        WEnum::Value(wl_output::Transform::Flipped90) => "flipped-90".to_string(),
+        WEnum::Value(wl_output::Transform::Flipped180) => "flipped-180".to_string(),
+        WEnum::Value(wl_output::Transform::Flipped270) => "flipped-270".to_string(),
+        _ => "normal".to_string(),
+    }
+}
+
+// Parse a transform display name produced by transform_to_string
+pub(crate) fn transform_from_str(transform: &str) -> Option<wl_output::Transform> {
+    match transform {
+        "normal" => Some(wl_output::Transform::Normal),
+        "90" => Some(wl_output::Transform::_90),
+        "180" => Some(wl_output::Transform::_180),
+        "270" => Some(wl_output::Transform::_270),
+        "flipped" => Some(wl_output::Transform::Flipped),
+        "flipped-90" => Some(wl_output::Transform::Flipped90),
+        "flipped-180" => Some(wl_output::Transform::Flipped180),
+        "flipped-270" => Some(wl_output::Transform::Flipped270),
+        _ => None,
+    }
 }
 
 // Handle wl_output events
@@ -1,10 +1,15 @@
+use memmap2::MmapOptions;
 use serde::Serialize;
+use std::fs::{self, File};
+use std::os::fd::OwnedFd;
+use std::path::Path;
 use wayland_client::{
     protocol::{wl_keyboard, wl_seat, wl_seat::WlSeat},
     Connection, Dispatch, QueueHandle, WEnum,
 };
 
 use crate::app::{AppData, UserData};
+use crate::keymap::{parse_xkb, XkbSummary};
 
 // Seat info structure
 #[derive(Debug, Clone, Serialize)]
@@ -16,6 +21,19 @@ pub struct SeatInfo {
     pub(crate) capabilities: Vec<String>,
     pub(crate) keyboard_repeat_rate: Option<i32>,
     pub(crate) keyboard_repeat_delay: Option<i32>,
+    pub(crate) keymap: Option<KeymapInfo>,
+}
+
+// Keyboard keymap info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeymapInfo {
+    pub(crate) format: String,
+    pub(crate) size: u32,
+    #[serde(flatten)]
+    pub(crate) summary: XkbSummary,
+    #[serde(skip_serializing)]
+    pub(crate) text: String,
 }
 
 impl AppData {
@@ -26,6 +44,7 @@ impl AppData {
             capabilities: Vec::new(),
             keyboard_repeat_rate: None,
             keyboard_repeat_delay: None,
+            keymap: None,
         });
     }
 
@@ -45,6 +64,57 @@ impl AppData {
             seat.keyboard_repeat_delay = Some(delay);
         }
     }
+
+    pub(crate) fn update_seat_keymap(
+        &mut self,
+        seat_index: usize,
+        format: WEnum<wl_keyboard::KeymapFormat>,
+        fd: OwnedFd,
+        size: u32,
+    ) {
+        let (format, text) = match format {
+            WEnum::Value(wl_keyboard::KeymapFormat::XkbV1) => {
+                ("xkb_v1", read_keymap(fd, size as usize).unwrap_or_default())
+            }
+            WEnum::Value(wl_keyboard::KeymapFormat::NoKeymap) => ("no_keymap", String::new()),
+            _ => ("unknown", String::new()),
+        };
+        if let Some(seat) = self.seats.get_mut(seat_index) {
+            seat.keymap = Some(KeymapInfo {
+                format: format.to_string(),
+                size,
+                summary: parse_xkb(&text),
+                text,
+            });
+        }
+    }
+
+    /// Write each seat's raw keymap to `<dir>/<seat-name>.xkb`.
+    pub(crate) fn dump_keymaps(&self, dir: &Path) -> Result<(), String> {
+        fs::create_dir_all(dir)
+            .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+        for seat in &self.seats {
+            let Some(keymap) = seat.keymap.as_ref().filter(|k| !k.text.is_empty()) else {
+                continue;
+            };
+            let path = dir.join(format!("{}.xkb", seat.seat_name));
+            fs::write(&path, &keymap.text)
+                .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+        }
+        Ok(())
+    }
+}
+
+// Map the keymap fd and return its text without the trailing NUL
+fn read_keymap(fd: OwnedFd, size: usize) -> Option<String> {
+    if size == 0 {
+        return None;
+    }
+    let file = File::from(fd);
+    // SAFETY: the mapping is private, so later changes by the compositor are not observed.
+    let map = unsafe { MmapOptions::new().len(size).map_copy_read_only(&file) }.ok()?;
+    let text = map.split(|&b| b == 0).next().unwrap_or_default();
+    Some(String::from_utf8_lossy(text).into_owned())
 }
 
 // Handle wl_seat events
@@ -102,8 +172,14 @@ impl Dispatch<wl_keyboard::WlKeyboard, UserData> for AppData {
     ) {
         if let UserData::Seat { seat_index } = data {
             state.mark_event();
-            if let wl_keyboard::Event::RepeatInfo { rate, delay } = event {
-                state.update_seat_keyboard_repeat(*seat_index, rate, delay);
+            match event {
+                wl_keyboard::Event::Keymap { format, fd, size } => {
+                    state.update_seat_keymap(*seat_index, format, fd, size);
+                }
+                wl_keyboard::Event::RepeatInfo { rate, delay } => {
+                    state.update_seat_keyboard_repeat(*seat_index, rate, delay);
+                }
+                _ => {}
             }
         }
     }
@@ -1,6 +1,8 @@
 use serde::Serialize;
 use wayland_client::{
-    protocol::{wl_shm, wl_shm::WlShm},
+    protocol::{
+        wl_buffer, wl_buffer::WlBuffer, wl_shm, wl_shm::WlShm, wl_shm_pool, wl_shm_pool::WlShmPool,
+    },
     Connection, Dispatch, QueueHandle, WEnum,
 };
 
@@ -46,7 +48,7 @@ impl AppData {
 }
 
 // Convert format code to FOURCC string
-fn format_to_fourcc(format: u32) -> String {
+pub(crate) fn format_to_fourcc(format: u32) -> String {
     let bytes = [
         (format & 0xFF) as u8,
         ((format >> 8) & 0xFF) as u8,
@@ -80,3 +82,30 @@ impl Dispatch<WlShm, UserData> for AppData {
         }
     }
 }
+
+// Pools and buffers are only allocated by the capture probe
+impl Dispatch<WlShmPool, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _pool: &WlShmPool,
+        _event: wl_shm_pool::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // WlShmPool has no events.
+    }
+}
+
+impl Dispatch<WlBuffer, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _buffer: &WlBuffer,
+        _event: wl_buffer::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // The buffer is never attached to a surface, so release is not expected.
+    }
+}
@@ -0,0 +1,212 @@
+use serde::Serialize;
+use wayland_client::{Connection, Dispatch, QueueHandle, WEnum};
+use wayland_protocols_wlr::export_dmabuf::v1::client::{
+    zwlr_export_dmabuf_frame_v1::{self, ZwlrExportDmabufFrameV1},
+    zwlr_export_dmabuf_manager_v1::{self, ZwlrExportDmabufManagerV1},
+};
+
+use crate::app::{AppData, UserData};
+use crate::capture_probe::PresentationTime;
+use crate::protocols::linux_dmabuf::modifier_to_string;
+use crate::protocols::wl_shm::format_to_fourcc;
+
+// zwp_linux_buffer_params_v1 flags carried in the frame event
+const BUFFER_FLAGS: [(u32, &str); 3] = [(1, "y_invert"), (2, "interlaced"), (4, "bottom_first")];
+
+// Attributes of one exported frame; the dmabuf fds are closed on receipt
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDmabufFrameInfo {
+    pub(crate) state: String,
+    pub(crate) cancel_reason: Option<String>,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) offset_x: u32,
+    pub(crate) offset_y: u32,
+    pub(crate) format: u32,
+    pub(crate) fourcc: String,
+    pub(crate) modifier: String,
+    pub(crate) buffer_flags: Vec<String>,
+    pub(crate) transient: bool,
+    pub(crate) num_objects: u32,
+    pub(crate) objects: Vec<ExportDmabufObject>,
+    pub(crate) presentation_time: Option<PresentationTime>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportDmabufObject {
+    pub(crate) index: u32,
+    pub(crate) plane_index: u32,
+    pub(crate) size: u32,
+    pub(crate) offset: u32,
+    pub(crate) stride: u32,
+}
+
+impl AppData {
+    /// Request one exported frame descriptor for every bound output.
+    pub(crate) fn get_export_dmabuf_frames(&mut self, qh: &QueueHandle<AppData>) {
+        let Some(manager) = self.export_dmabuf_manager_objects.first() else {
+            return;
+        };
+        for (output_index, output) in self.output_objects.iter().enumerate() {
+            let _frame =
+                manager.capture_output(0, output, qh, UserData::ExportDmabufFrame { output_index });
+            if let Some(info) = self.outputs.get_mut(output_index) {
+                info.export_dmabuf_frame = Some(ExportDmabufFrameInfo {
+                    state: "pending".to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+}
+
+fn cancel_reason_to_string(reason: WEnum<zwlr_export_dmabuf_frame_v1::CancelReason>) -> String {
+    match reason {
+        WEnum::Value(zwlr_export_dmabuf_frame_v1::CancelReason::Temporary) => "temporary",
+        WEnum::Value(zwlr_export_dmabuf_frame_v1::CancelReason::Permanent) => "permanent",
+        WEnum::Value(zwlr_export_dmabuf_frame_v1::CancelReason::Resizing) => "resizing",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+fn buffer_flags_to_strings(buffer_flags: u32) -> Vec<String> {
+    BUFFER_FLAGS
+        .iter()
+        .filter(|(bit, _)| buffer_flags & bit != 0)
+        .map(|(_, name)| name.to_string())
+        .collect()
+}
+
+// Handle zwlr_export_dmabuf_manager_v1 events
+impl Dispatch<ZwlrExportDmabufManagerV1, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _manager: &ZwlrExportDmabufManagerV1,
+        _event: zwlr_export_dmabuf_manager_v1::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // ZwlrExportDmabufManagerV1 has no events.
+    }
+}
+
+// Handle zwlr_export_dmabuf_frame_v1 events
+impl Dispatch<ZwlrExportDmabufFrameV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        frame: &ZwlrExportDmabufFrameV1,
+        event: zwlr_export_dmabuf_frame_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::ExportDmabufFrame { output_index } = data {
+            state.mark_event();
+            let Some(info) = state
+                .outputs
+                .get_mut(*output_index)
+                .and_then(|output| output.export_dmabuf_frame.as_mut())
+            else {
+                return;
+            };
+            match event {
+                zwlr_export_dmabuf_frame_v1::Event::Frame {
+                    width,
+                    height,
+                    offset_x,
+                    offset_y,
+                    buffer_flags,
+                    flags,
+                    format,
+                    mod_high,
+                    mod_low,
+                    num_objects,
+                } => {
+                    let flags = match flags {
+                        WEnum::Value(value) => value as u32,
+                        WEnum::Unknown(value) => value,
+                    };
+                    info.width = width;
+                    info.height = height;
+                    info.offset_x = offset_x;
+                    info.offset_y = offset_y;
+                    info.format = format;
+                    info.fourcc = format_to_fourcc(format);
+                    info.modifier = modifier_to_string(((mod_high as u64) << 32) | mod_low as u64);
+                    info.buffer_flags = buffer_flags_to_strings(buffer_flags);
+                    info.transient =
+                        flags & zwlr_export_dmabuf_frame_v1::Flags::Transient as u32 != 0;
+                    info.num_objects = num_objects;
+                }
+                zwlr_export_dmabuf_frame_v1::Event::Object {
+                    index,
+                    fd,
+                    size,
+                    offset,
+                    stride,
+                    plane_index,
+                } => {
+                    // Only the plane layout is recorded; close the dmabuf right away
+                    drop(fd);
+                    info.objects.push(ExportDmabufObject {
+                        index,
+                        plane_index,
+                        size,
+                        offset,
+                        stride,
+                    });
+                }
+                zwlr_export_dmabuf_frame_v1::Event::Ready {
+                    tv_sec_hi,
+                    tv_sec_lo,
+                    tv_nsec,
+                } => {
+                    info.state = "ready".to_string();
+                    info.presentation_time =
+                        Some(PresentationTime::new(tv_sec_hi, tv_sec_lo, tv_nsec));
+                    frame.destroy();
+                }
+                zwlr_export_dmabuf_frame_v1::Event::Cancel { reason } => {
+                    info.state = "cancelled".to_string();
+                    info.cancel_reason = Some(cancel_reason_to_string(reason));
+                    frame.destroy();
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buffer_flags_use_linux_dmabuf_names() {
+        assert!(buffer_flags_to_strings(0).is_empty());
+        assert_eq!(buffer_flags_to_strings(1), ["y_invert"]);
+        assert_eq!(
+            buffer_flags_to_strings(2 | 4 | 8),
+            ["interlaced", "bottom_first"]
+        );
+    }
+
+    // Clients retry on temporary and resizing; permanent means give up
+    #[test]
+    fn cancel_reasons_use_protocol_names() {
+        use zwlr_export_dmabuf_frame_v1::CancelReason;
+        assert_eq!(
+            cancel_reason_to_string(WEnum::Value(CancelReason::Permanent)),
+            "permanent"
+        );
+        assert_eq!(
+            cancel_reason_to_string(WEnum::Value(CancelReason::Resizing)),
+            "resizing"
+        );
+        assert_eq!(cancel_reason_to_string(WEnum::Unknown(3)), "unknown");
+    }
+}
@@ -0,0 +1,207 @@
+use serde::Serialize;
+use wayland_client::{
+    event_created_child, protocol::wl_output::WlOutput, Connection, Dispatch, Proxy, QueueHandle,
+};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+    zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+};
+
+use crate::app::{AppData, UserData};
+
+// wlr foreign toplevel manager info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WlrToplevelManagerInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) toplevels: Vec<WlrToplevelInfo>,
+}
+
+// wlr foreign toplevel info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WlrToplevelInfo {
+    #[serde(skip_serializing)]
+    pub(crate) handle: ZwlrForeignToplevelHandleV1,
+    pub(crate) id: u32,
+    pub(crate) title: String,
+    pub(crate) app_id: String,
+    pub(crate) state: Vec<String>,
+    pub(crate) outputs: Vec<String>,
+    pub(crate) parent: Option<u32>,
+    #[serde(skip_serializing)]
+    pub(crate) output_objects: Vec<WlOutput>,
+}
+
+impl AppData {
+    pub(crate) fn add_wlr_toplevel_manager(&mut self, name: u32) {
+        self.wlr_toplevel_managers.push(WlrToplevelManagerInfo {
+            name,
+            toplevels: Vec::new(),
+        });
+    }
+
+    pub(crate) fn add_wlr_toplevel(
+        &mut self,
+        manager_index: usize,
+        handle: ZwlrForeignToplevelHandleV1,
+    ) {
+        if let Some(manager) = self.wlr_toplevel_managers.get_mut(manager_index) {
+            manager.toplevels.push(WlrToplevelInfo {
+                id: handle.id().protocol_id(),
+                handle,
+                title: String::new(),
+                app_id: String::new(),
+                state: Vec::new(),
+                outputs: Vec::new(),
+                parent: None,
+                output_objects: Vec::new(),
+            });
+        }
+    }
+
+    fn wlr_toplevel_mut(
+        &mut self,
+        handle: &ZwlrForeignToplevelHandleV1,
+    ) -> Option<&mut WlrToplevelInfo> {
+        self.wlr_toplevel_managers
+            .iter_mut()
+            .flat_map(|manager| manager.toplevels.iter_mut())
+            .find(|toplevel| &toplevel.handle == handle)
+    }
+
+    /// Name the outputs each toplevel is on after `OutputInfo`.
+    /// Call once all events have been dispatched.
+    pub(crate) fn resolve_wlr_toplevels(&mut self) {
+        let mut managers = std::mem::take(&mut self.wlr_toplevel_managers);
+        for toplevel in managers.iter_mut().flat_map(|m| m.toplevels.iter_mut()) {
+            toplevel.outputs = toplevel
+                .output_objects
+                .iter()
+                .filter_map(|output| self.output_name(output))
+                .collect();
+        }
+        self.wlr_toplevel_managers = managers;
+    }
+}
+
+fn state_to_strings(state: &[u8]) -> Vec<String> {
+    state
+        .chunks_exact(4)
+        .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+        .map(|value| {
+            match zwlr_foreign_toplevel_handle_v1::State::try_from(value) {
+                Ok(zwlr_foreign_toplevel_handle_v1::State::Maximized) => "maximized",
+                Ok(zwlr_foreign_toplevel_handle_v1::State::Minimized) => "minimized",
+                Ok(zwlr_foreign_toplevel_handle_v1::State::Activated) => "activated",
+                Ok(zwlr_foreign_toplevel_handle_v1::State::Fullscreen) => "fullscreen",
+                _ => "unknown",
+            }
+            .to_string()
+        })
+        .collect()
+}
+
+// Handle zwlr_foreign_toplevel_manager_v1 events
+impl Dispatch<ZwlrForeignToplevelManagerV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _manager: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::WlrToplevelManager { manager_index } = data {
+            state.mark_event();
+            if let zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } = event {
+                state.add_wlr_toplevel(*manager_index, toplevel);
+            }
+        }
+    }
+
+    event_created_child!(AppData, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, UserData::WlrToplevel),
+    ]);
+}
+
+// Handle zwlr_foreign_toplevel_handle_v1 events
+impl Dispatch<ZwlrForeignToplevelHandleV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::WlrToplevel = data {
+            state.mark_event();
+            if let zwlr_foreign_toplevel_handle_v1::Event::Closed = event {
+                for manager in &mut state.wlr_toplevel_managers {
+                    manager
+                        .toplevels
+                        .retain(|toplevel| &toplevel.handle != handle);
+                }
+                handle.destroy();
+                return;
+            }
+            let Some(toplevel) = state.wlr_toplevel_mut(handle) else {
+                return;
+            };
+            match event {
+                zwlr_foreign_toplevel_handle_v1::Event::Title { title } => {
+                    toplevel.title = title;
+                }
+                zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => {
+                    toplevel.app_id = app_id;
+                }
+                zwlr_foreign_toplevel_handle_v1::Event::OutputEnter { output } => {
+                    toplevel.output_objects.push(output);
+                }
+                zwlr_foreign_toplevel_handle_v1::Event::OutputLeave { output } => {
+                    toplevel.output_objects.retain(|o| o != &output);
+                }
+                zwlr_foreign_toplevel_handle_v1::Event::State { state } => {
+                    toplevel.state = state_to_strings(&state);
+                }
+                zwlr_foreign_toplevel_handle_v1::Event::Parent { parent } => {
+                    toplevel.parent = parent.map(|p| p.id().protocol_id());
+                }
+                zwlr_foreign_toplevel_handle_v1::Event::Done => {}
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The state event carries a wl_array of native-endian u32 enum values
+    fn state_array(values: &[u32]) -> Vec<u8> {
+        values.iter().flat_map(|v| v.to_ne_bytes()).collect()
+    }
+
+    #[test]
+    fn decodes_state_array() {
+        assert!(state_to_strings(&[]).is_empty());
+        assert_eq!(
+            state_to_strings(&state_array(&[2, 0])),
+            ["activated", "maximized"]
+        );
+        assert_eq!(
+            state_to_strings(&state_array(&[3, 1, 9])),
+            ["fullscreen", "minimized", "unknown"]
+        );
+    }
+
+    #[test]
+    fn trailing_partial_value_is_ignored() {
+        let mut state = state_array(&[1]);
+        state.extend_from_slice(&[3, 0]);
+        assert_eq!(state_to_strings(&state), ["minimized"]);
+    }
+}
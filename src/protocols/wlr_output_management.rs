@@ -0,0 +1,378 @@
+use serde::Serialize;
+use wayland_client::{event_created_child, Connection, Dispatch, Proxy, QueueHandle, WEnum};
+use wayland_protocols_wlr::output_management::v1::client::{
+    zwlr_output_configuration_head_v1::{self, ZwlrOutputConfigurationHeadV1},
+    zwlr_output_configuration_v1::{self, ZwlrOutputConfigurationV1},
+    zwlr_output_head_v1::{self, ZwlrOutputHeadV1},
+    zwlr_output_manager_v1::{self, ZwlrOutputManagerV1},
+    zwlr_output_mode_v1::{self, ZwlrOutputModeV1},
+};
+
+use crate::app::{AppData, UserData};
+use crate::protocols::wl_output::transform_to_string;
+
+// wlr output manager info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WlrOutputManagerInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    #[serde(skip_serializing)]
+    pub(crate) serial: Option<u32>,
+    pub(crate) heads: Vec<WlrOutputHeadInfo>,
+}
+
+// wlr output head info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WlrOutputHeadInfo {
+    #[serde(skip_serializing)]
+    pub(crate) head: ZwlrOutputHeadV1,
+    pub(crate) name: String,
+    pub(crate) description: String,
+    pub(crate) make: String,
+    pub(crate) model: String,
+    pub(crate) serial_number: String,
+    pub(crate) physical_width: i32,
+    pub(crate) physical_height: i32,
+    pub(crate) enabled: bool,
+    pub(crate) x: i32,
+    pub(crate) y: i32,
+    pub(crate) transform: String,
+    pub(crate) scale: f64,
+    pub(crate) adaptive_sync: Option<String>,
+    pub(crate) modes: Vec<WlrOutputModeInfo>,
+}
+
+// wlr output mode info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WlrOutputModeInfo {
+    #[serde(skip_serializing)]
+    pub(crate) mode: ZwlrOutputModeV1,
+    pub(crate) width: i32,
+    pub(crate) height: i32,
+    pub(crate) refresh: i32,
+    pub(crate) preferred: bool,
+    pub(crate) current: bool,
+}
+
+// Compositor response to a zwlr_output_configuration_v1 test or apply
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigurationResult {
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+impl ConfigurationResult {
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            ConfigurationResult::Succeeded => "succeeded",
+            ConfigurationResult::Failed => "failed",
+            ConfigurationResult::Cancelled => "cancelled",
+        }
+    }
+}
+
+impl AppData {
+    pub(crate) fn add_wlr_output_manager(&mut self, name: u32) {
+        self.wlr_output_managers.push(WlrOutputManagerInfo {
+            name,
+            serial: None,
+            heads: Vec::new(),
+        });
+    }
+
+    pub(crate) fn add_wlr_output_head(&mut self, manager_index: usize, head: ZwlrOutputHeadV1) {
+        if let Some(manager) = self.wlr_output_managers.get_mut(manager_index) {
+            manager.heads.push(WlrOutputHeadInfo {
+                head,
+                name: String::new(),
+                description: String::new(),
+                make: String::new(),
+                model: String::new(),
+                serial_number: String::new(),
+                physical_width: 0,
+                physical_height: 0,
+                enabled: false,
+                x: 0,
+                y: 0,
+                transform: "normal".to_string(),
+                scale: 1.0,
+                adaptive_sync: None,
+                modes: Vec::new(),
+            });
+        }
+    }
+
+    pub(crate) fn update_wlr_output_serial(&mut self, manager_index: usize, serial: u32) {
+        if let Some(manager) = self.wlr_output_managers.get_mut(manager_index) {
+            manager.serial = Some(serial);
+        }
+    }
+
+    /// Find the wlr head describing the `wl_output` with the given name.
+    pub(crate) fn wlr_output_head(&self, output_name: &str) -> Option<&WlrOutputHeadInfo> {
+        self.wlr_output_managers
+            .iter()
+            .flat_map(|manager| manager.heads.iter())
+            .find(|info| !output_name.is_empty() && info.name == output_name)
+    }
+
+    /// List the heads of a manager that no `wl_output` describes, such as
+    /// disabled outputs; the others are reported under their `wl_output`.
+    pub(crate) fn wlr_output_heads_without_output<'a>(
+        &self,
+        manager: &'a WlrOutputManagerInfo,
+    ) -> Vec<&'a WlrOutputHeadInfo> {
+        manager
+            .heads
+            .iter()
+            .filter(|head| !self.outputs.iter().any(|o| o.output_name == head.name))
+            .collect()
+    }
+
+    fn remove_wlr_output_head(&mut self, head: &ZwlrOutputHeadV1) {
+        for manager in &mut self.wlr_output_managers {
+            manager.heads.retain(|info| &info.head != head);
+        }
+    }
+
+    fn remove_wlr_output_mode(&mut self, mode: &ZwlrOutputModeV1) {
+        for head in self
+            .wlr_output_managers
+            .iter_mut()
+            .flat_map(|manager| manager.heads.iter_mut())
+        {
+            head.modes.retain(|info| &info.mode != mode);
+        }
+    }
+
+    fn wlr_output_head_mut(&mut self, head: &ZwlrOutputHeadV1) -> Option<&mut WlrOutputHeadInfo> {
+        self.wlr_output_managers
+            .iter_mut()
+            .flat_map(|manager| manager.heads.iter_mut())
+            .find(|info| &info.head == head)
+    }
+
+    fn wlr_output_mode_mut(&mut self, mode: &ZwlrOutputModeV1) -> Option<&mut WlrOutputModeInfo> {
+        self.wlr_output_managers
+            .iter_mut()
+            .flat_map(|manager| manager.heads.iter_mut())
+            .flat_map(|head| head.modes.iter_mut())
+            .find(|info| &info.mode == mode)
+    }
+}
+
+// Handle zwlr_output_manager_v1 events
+impl Dispatch<ZwlrOutputManagerV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _manager: &ZwlrOutputManagerV1,
+        event: zwlr_output_manager_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::WlrOutputManager { manager_index } = data {
+            state.mark_event();
+            match event {
+                zwlr_output_manager_v1::Event::Head { head } => {
+                    state.add_wlr_output_head(*manager_index, head);
+                }
+                zwlr_output_manager_v1::Event::Done { serial } => {
+                    state.update_wlr_output_serial(*manager_index, serial);
+                }
+                // The compositor destroys the manager after this event; without a
+                // serial no configuration can be built against its heads
+                zwlr_output_manager_v1::Event::Finished => {
+                    if let Some(manager) = state.wlr_output_managers.get_mut(*manager_index) {
+                        manager.serial = None;
+                        manager.heads.clear();
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    event_created_child!(AppData, ZwlrOutputManagerV1, [
+        zwlr_output_manager_v1::EVT_HEAD_OPCODE => (ZwlrOutputHeadV1, UserData::WlrOutputHead),
+    ]);
+}
+
+// Handle zwlr_output_head_v1 events
+impl Dispatch<ZwlrOutputHeadV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        head: &ZwlrOutputHeadV1,
+        event: zwlr_output_head_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::WlrOutputHead = data {
+            state.mark_event();
+            // The head is gone; drop it so layouts are not built against it
+            if let zwlr_output_head_v1::Event::Finished = event {
+                state.remove_wlr_output_head(head);
+                if head.version() >= 3 {
+                    head.release();
+                }
+                return;
+            }
+            let Some(info) = state.wlr_output_head_mut(head) else {
+                return;
+            };
+            match event {
+                zwlr_output_head_v1::Event::Name { name } => {
+                    info.name = name;
+                }
+                zwlr_output_head_v1::Event::Description { description } => {
+                    info.description = description;
+                }
+                zwlr_output_head_v1::Event::PhysicalSize { width, height } => {
+                    info.physical_width = width;
+                    info.physical_height = height;
+                }
+                zwlr_output_head_v1::Event::Mode { mode } => {
+                    info.modes.push(WlrOutputModeInfo {
+                        mode,
+                        width: 0,
+                        height: 0,
+                        refresh: 0,
+                        preferred: false,
+                        current: false,
+                    });
+                }
+                zwlr_output_head_v1::Event::Enabled { enabled } => {
+                    info.enabled = enabled != 0;
+                }
+                zwlr_output_head_v1::Event::CurrentMode { mode } => {
+                    for info_mode in &mut info.modes {
+                        info_mode.current = info_mode.mode == mode;
+                    }
+                }
+                zwlr_output_head_v1::Event::Position { x, y } => {
+                    info.x = x;
+                    info.y = y;
+                }
+                zwlr_output_head_v1::Event::Transform { transform } => {
+                    info.transform = transform_to_string(transform);
+                }
+                zwlr_output_head_v1::Event::Scale { scale } => {
+                    info.scale = scale;
+                }
+                zwlr_output_head_v1::Event::Make { make } => {
+                    info.make = make;
+                }
+                zwlr_output_head_v1::Event::Model { model } => {
+                    info.model = model;
+                }
+                zwlr_output_head_v1::Event::SerialNumber { serial_number } => {
+                    info.serial_number = serial_number;
+                }
+                zwlr_output_head_v1::Event::AdaptiveSync { state } => {
+                    info.adaptive_sync = Some(
+                        match state {
+                            WEnum::Value(zwlr_output_head_v1::AdaptiveSyncState::Enabled) => {
+                                "enabled"
+                            }
+                            WEnum::Value(zwlr_output_head_v1::AdaptiveSyncState::Disabled) => {
+                                "disabled"
+                            }
+                            _ => "unknown",
+                        }
+                        .to_string(),
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+
+    event_created_child!(AppData, ZwlrOutputHeadV1, [
+        zwlr_output_head_v1::EVT_MODE_OPCODE => (ZwlrOutputModeV1, UserData::WlrOutputMode),
+    ]);
+}
+
+// Handle zwlr_output_mode_v1 events
+impl Dispatch<ZwlrOutputModeV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        mode: &ZwlrOutputModeV1,
+        event: zwlr_output_mode_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::WlrOutputMode = data {
+            state.mark_event();
+            if let zwlr_output_mode_v1::Event::Finished = event {
+                state.remove_wlr_output_mode(mode);
+                if mode.version() >= 3 {
+                    mode.release();
+                }
+                return;
+            }
+            let Some(info) = state.wlr_output_mode_mut(mode) else {
+                return;
+            };
+            match event {
+                zwlr_output_mode_v1::Event::Size { width, height } => {
+                    info.width = width;
+                    info.height = height;
+                }
+                zwlr_output_mode_v1::Event::Refresh { refresh } => {
+                    info.refresh = refresh;
+                }
+                zwlr_output_mode_v1::Event::Preferred => {
+                    info.preferred = true;
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// Handle zwlr_output_configuration_v1 events
+impl Dispatch<ZwlrOutputConfigurationV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _configuration: &ZwlrOutputConfigurationV1,
+        event: zwlr_output_configuration_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::WlrOutputConfiguration = data {
+            state.mark_event();
+            state.wlr_configuration_result = match event {
+                zwlr_output_configuration_v1::Event::Succeeded => {
+                    Some(ConfigurationResult::Succeeded)
+                }
+                zwlr_output_configuration_v1::Event::Failed => Some(ConfigurationResult::Failed),
+                zwlr_output_configuration_v1::Event::Cancelled => {
+                    Some(ConfigurationResult::Cancelled)
+                }
+                _ => None,
+            };
+        }
+    }
+}
+
+// Handle zwlr_output_configuration_head_v1 events
+impl Dispatch<ZwlrOutputConfigurationHeadV1, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _head: &ZwlrOutputConfigurationHeadV1,
+        _event: zwlr_output_configuration_head_v1::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // zwlr_output_configuration_head_v1 has no events.
+    }
+}
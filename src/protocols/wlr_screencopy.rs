@@ -0,0 +1,256 @@
+use serde::Serialize;
+use wayland_client::{Connection, Dispatch, Proxy, QueueHandle, WEnum};
+use wayland_protocols_wlr::screencopy::v1::client::{
+    zwlr_screencopy_frame_v1::{self, ZwlrScreencopyFrameV1},
+    zwlr_screencopy_manager_v1::{self, ZwlrScreencopyManagerV1},
+};
+
+use crate::app::{AppData, UserData};
+use crate::capture_probe::{CaptureProbeBuffer, PresentationTime};
+use crate::protocols::wl_shm::format_to_fourcc;
+
+// Screencopy manager info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreencopyManagerInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) frames: Vec<ScreencopyFrameInfo>,
+}
+
+// Buffer parameters of one frame request; the frame is never copied
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreencopyFrameInfo {
+    #[serde(skip_serializing)]
+    pub(crate) output_index: usize,
+    pub(crate) output: String,
+    pub(crate) overlay_cursor: bool,
+    pub(crate) state: String,
+    pub(crate) buffer: Option<ScreencopyShmBuffer>,
+    pub(crate) linux_dmabuf: Option<ScreencopyDmabufBuffer>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreencopyShmBuffer {
+    pub(crate) format: u32,
+    pub(crate) fourcc: String,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    pub(crate) stride: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreencopyDmabufBuffer {
+    pub(crate) format: u32,
+    pub(crate) fourcc: String,
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+}
+
+impl AppData {
+    pub(crate) fn add_screencopy_manager(&mut self, name: u32) {
+        self.screencopy_managers.push(ScreencopyManagerInfo {
+            name,
+            frames: Vec::new(),
+        });
+    }
+
+    /// Request a frame for every bound output, with and without the cursor,
+    /// from every screencopy manager.
+    pub(crate) fn get_screencopy_frames(&mut self, qh: &QueueHandle<AppData>) {
+        for (manager_index, manager) in self.screencopy_manager_objects.iter().enumerate() {
+            for (output_index, output) in self.output_objects.iter().enumerate() {
+                for overlay_cursor in [false, true] {
+                    let Some(info) = self.screencopy_managers.get_mut(manager_index) else {
+                        continue;
+                    };
+                    let frame_index = info.frames.len();
+                    info.frames.push(ScreencopyFrameInfo {
+                        output_index,
+                        output: String::new(),
+                        overlay_cursor,
+                        state: "pending".to_string(),
+                        buffer: None,
+                        linux_dmabuf: None,
+                    });
+                    let _frame = manager.capture_output(
+                        overlay_cursor as i32,
+                        output,
+                        qh,
+                        UserData::ScreencopyFrame {
+                            manager_index,
+                            frame_index,
+                        },
+                    );
+                }
+            }
+        }
+    }
+
+    /// Name each screencopy frame after its `OutputInfo`.
+    /// Call once all events have been dispatched.
+    pub(crate) fn resolve_screencopy_frames(&mut self) {
+        for manager in &mut self.screencopy_managers {
+            for frame in &mut manager.frames {
+                if let Some(output) = self.outputs.get(frame.output_index) {
+                    frame.output = output.output_name.clone();
+                }
+            }
+        }
+    }
+
+    fn screencopy_frame_mut(
+        &mut self,
+        manager_index: usize,
+        frame_index: usize,
+    ) -> Option<&mut ScreencopyFrameInfo> {
+        self.screencopy_managers
+            .get_mut(manager_index)?
+            .frames
+            .get_mut(frame_index)
+    }
+}
+
+// Handle zwlr_screencopy_manager_v1 events
+impl Dispatch<ZwlrScreencopyManagerV1, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _manager: &ZwlrScreencopyManagerV1,
+        _event: zwlr_screencopy_manager_v1::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // ZwlrScreencopyManagerV1 has no events.
+    }
+}
+
+// Handle zwlr_screencopy_frame_v1 events
+impl Dispatch<ZwlrScreencopyFrameV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        frame: &ZwlrScreencopyFrameV1,
+        event: zwlr_screencopy_frame_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::ScreencopyFrame {
+            manager_index,
+            frame_index,
+        } = data
+        {
+            state.mark_event();
+            let Some(info) = state.screencopy_frame_mut(*manager_index, *frame_index) else {
+                return;
+            };
+            match event {
+                zwlr_screencopy_frame_v1::Event::Buffer {
+                    format,
+                    width,
+                    height,
+                    stride,
+                } => {
+                    let format = match format {
+                        WEnum::Value(value) => value as u32,
+                        WEnum::Unknown(value) => value,
+                    };
+                    info.buffer = Some(ScreencopyShmBuffer {
+                        format,
+                        fourcc: format_to_fourcc(format),
+                        width,
+                        height,
+                        stride,
+                    });
+                    // Before version 3 the buffer event is the only buffer parameter
+                    if frame.version() < 3 {
+                        info.state = "done".to_string();
+                        frame.destroy();
+                    }
+                }
+                zwlr_screencopy_frame_v1::Event::LinuxDmabuf {
+                    format,
+                    width,
+                    height,
+                } => {
+                    info.linux_dmabuf = Some(ScreencopyDmabufBuffer {
+                        format,
+                        fourcc: format_to_fourcc(format),
+                        width,
+                        height,
+                    });
+                }
+                // All buffer types are known; destroy the frame instead of copying it
+                zwlr_screencopy_frame_v1::Event::BufferDone => {
+                    info.state = "done".to_string();
+                    frame.destroy();
+                }
+                zwlr_screencopy_frame_v1::Event::Failed => {
+                    info.state = "failed".to_string();
+                    frame.destroy();
+                }
+                _ => {}
+            }
+        } else if let UserData::CaptureProbeFrame { probe_index } = data {
+            state.mark_event();
+            let Some(result) = state.capture_probe_results.get_mut(*probe_index) else {
+                return;
+            };
+            match event {
+                zwlr_screencopy_frame_v1::Event::Buffer {
+                    format,
+                    width,
+                    height,
+                    stride,
+                } => {
+                    let format = match format {
+                        WEnum::Value(value) => value as u32,
+                        WEnum::Unknown(value) => value,
+                    };
+                    result.buffer = Some(CaptureProbeBuffer {
+                        format,
+                        fourcc: format_to_fourcc(format),
+                        width,
+                        height,
+                        stride,
+                    });
+                    result.buffer_done = frame.version() < 3;
+                }
+                zwlr_screencopy_frame_v1::Event::BufferDone => {
+                    result.buffer_done = true;
+                }
+                zwlr_screencopy_frame_v1::Event::Flags { flags } => {
+                    let flags = match flags {
+                        WEnum::Value(value) => value,
+                        WEnum::Unknown(value) => {
+                            zwlr_screencopy_frame_v1::Flags::from_bits_retain(value)
+                        }
+                    };
+                    if flags.contains(zwlr_screencopy_frame_v1::Flags::YInvert) {
+                        result.flags.push("y_invert".to_string());
+                    }
+                }
+                zwlr_screencopy_frame_v1::Event::Ready {
+                    tv_sec_hi,
+                    tv_sec_lo,
+                    tv_nsec,
+                } => {
+                    result.status = "ready".to_string();
+                    result.presentation_time =
+                        Some(PresentationTime::new(tv_sec_hi, tv_sec_lo, tv_nsec));
+                    result.screencopy_frame = None;
+                    frame.destroy();
+                }
+                zwlr_screencopy_frame_v1::Event::Failed => {
+                    result.fail("compositor failed the copy");
+                    result.screencopy_frame = None;
+                    frame.destroy();
+                }
+                _ => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,521 @@
+use memmap2::MmapOptions;
+use serde::Serialize;
+use std::fs::{self, File};
+use std::os::fd::OwnedFd;
+use std::path::Path;
+use wayland_client::{Connection, Dispatch, QueueHandle, WEnum};
+use wayland_protocols::wp::color_management::v1::client::{
+    wp_color_management_output_v1::{self, WpColorManagementOutputV1},
+    wp_color_manager_v1::{self, WpColorManagerV1},
+    wp_image_description_creator_params_v1::{self, WpImageDescriptionCreatorParamsV1},
+    wp_image_description_info_v1::{self, WpImageDescriptionInfoV1},
+    wp_image_description_v1::{self, WpImageDescriptionV1},
+};
+
+use crate::app::{AppData, UserData};
+use crate::icc::profile_description;
+
+// Color manager info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorManagerInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) intents: Vec<String>,
+    pub(crate) features: Vec<String>,
+    pub(crate) transfer_functions: Vec<String>,
+    pub(crate) primaries: Vec<String>,
+    #[serde(skip_serializing)]
+    pub(crate) named_transfer_functions: Vec<wp_color_manager_v1::TransferFunction>,
+    #[serde(skip_serializing)]
+    pub(crate) named_primaries: Vec<wp_color_manager_v1::Primaries>,
+}
+
+// Image description info structure, as reported by wp_image_description_info_v1
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageDescriptionInfo {
+    pub(crate) state: String,
+    pub(crate) identity: Option<u64>,
+    pub(crate) failure: Option<String>,
+    pub(crate) icc_size: Option<u32>,
+    pub(crate) icc_description: Option<String>,
+    pub(crate) icc_path: Option<String>,
+    #[serde(skip_serializing)]
+    pub(crate) icc_data: Vec<u8>,
+    pub(crate) primaries: Option<Chromaticities>,
+    pub(crate) primaries_named: Option<String>,
+    pub(crate) tf_named: Option<String>,
+    pub(crate) tf_power: Option<f64>,
+    pub(crate) luminances: Option<Luminances>,
+    pub(crate) target_primaries: Option<Chromaticities>,
+    pub(crate) target_luminance: Option<Luminances>,
+    pub(crate) target_max_cll: Option<u32>,
+    pub(crate) target_max_fall: Option<u32>,
+}
+
+// CIE 1931 xy chromaticity coordinates of the primaries and white point
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Chromaticities {
+    pub(crate) red: [f64; 2],
+    pub(crate) green: [f64; 2],
+    pub(crate) blue: [f64; 2],
+    pub(crate) white: [f64; 2],
+}
+
+// Luminances in cd/m2; the reference level is only set for the primary color volume
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Luminances {
+    pub(crate) min: f64,
+    pub(crate) max: f64,
+    pub(crate) reference: Option<u32>,
+}
+
+impl Chromaticities {
+    // Coordinates arrive as red, green, blue and white x/y pairs multiplied by 1M
+    fn from_protocol(values: [i32; 8]) -> Self {
+        let xy = |i: usize| {
+            [
+                values[i] as f64 / 1_000_000.0,
+                values[i + 1] as f64 / 1_000_000.0,
+            ]
+        };
+        Self {
+            red: xy(0),
+            green: xy(2),
+            blue: xy(4),
+            white: xy(6),
+        }
+    }
+}
+
+impl Luminances {
+    // The minimum arrives in units of 0.0001 cd/m2, the maximum in whole cd/m2
+    fn from_protocol(min_lum: u32, max_lum: u32, reference: Option<u32>) -> Self {
+        Self {
+            min: min_lum as f64 / 10_000.0,
+            max: max_lum as f64,
+            reference,
+        }
+    }
+}
+
+impl AppData {
+    pub(crate) fn add_color_manager(&mut self, name: u32) {
+        self.color_managers.push(ColorManagerInfo {
+            name,
+            intents: Vec::new(),
+            features: Vec::new(),
+            transfer_functions: Vec::new(),
+            primaries: Vec::new(),
+            named_transfer_functions: Vec::new(),
+            named_primaries: Vec::new(),
+        });
+    }
+
+    /// Request the image description of every bound output from the first color manager.
+    pub(crate) fn get_color_management_outputs(&mut self, qh: &QueueHandle<AppData>) {
+        let Some(manager) = self.color_manager_objects.first() else {
+            return;
+        };
+        for (output_index, output) in self.output_objects.iter().enumerate() {
+            let color_output =
+                manager.get_output(output, qh, UserData::ColorManagementOutput { output_index });
+            color_output.get_image_description(qh, UserData::ImageDescription { output_index });
+            if let Some(info) = self.outputs.get_mut(output_index) {
+                info.image_description = Some(ImageDescriptionInfo {
+                    state: "pending".to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    /// Write each output's ICC profile to `<dir>/<output-name>.icc`.
+    pub(crate) fn export_icc_profiles(&mut self, dir: &Path) -> Result<(), String> {
+        fs::create_dir_all(dir)
+            .map_err(|err| format!("Failed to create {}: {}", dir.display(), err))?;
+        for output in &mut self.outputs {
+            let Some(description) = output
+                .image_description
+                .as_mut()
+                .filter(|d| !d.icc_data.is_empty())
+            else {
+                continue;
+            };
+            let path = dir.join(format!("{}.icc", output.output_name));
+            fs::write(&path, &description.icc_data)
+                .map_err(|err| format!("Failed to write {}: {}", path.display(), err))?;
+            description.icc_path = Some(path.display().to_string());
+        }
+        Ok(())
+    }
+
+    fn output_image_description_mut(
+        &mut self,
+        output_index: usize,
+    ) -> Option<&mut ImageDescriptionInfo> {
+        self.outputs
+            .get_mut(output_index)?
+            .image_description
+            .as_mut()
+    }
+}
+
+// Map the ICC profile fd; it is read-only and must be mapped privately
+fn read_icc_file(fd: OwnedFd, size: usize) -> Option<Vec<u8>> {
+    if size == 0 {
+        return None;
+    }
+    let file = File::from(fd);
+    // SAFETY: the mapping is private and only read while the fd is open.
+    let map = unsafe { MmapOptions::new().len(size).map_copy_read_only(&file) }.ok()?;
+    Some(map.to_vec())
+}
+
+fn render_intent_to_string(intent: WEnum<wp_color_manager_v1::RenderIntent>) -> String {
+    match intent {
+        WEnum::Value(wp_color_manager_v1::RenderIntent::Perceptual) => "perceptual",
+        WEnum::Value(wp_color_manager_v1::RenderIntent::Relative) => "relative",
+        WEnum::Value(wp_color_manager_v1::RenderIntent::Saturation) => "saturation",
+        WEnum::Value(wp_color_manager_v1::RenderIntent::Absolute) => "absolute",
+        WEnum::Value(wp_color_manager_v1::RenderIntent::RelativeBpc) => "relative_bpc",
+        WEnum::Value(wp_color_manager_v1::RenderIntent::AbsoluteNoAdaptation) => {
+            "absolute_no_adaptation"
+        }
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+fn feature_to_string(feature: WEnum<wp_color_manager_v1::Feature>) -> String {
+    match feature {
+        WEnum::Value(wp_color_manager_v1::Feature::IccV2V4) => "icc_v2_v4",
+        WEnum::Value(wp_color_manager_v1::Feature::Parametric) => "parametric",
+        WEnum::Value(wp_color_manager_v1::Feature::SetPrimaries) => "set_primaries",
+        WEnum::Value(wp_color_manager_v1::Feature::SetTfPower) => "set_tf_power",
+        WEnum::Value(wp_color_manager_v1::Feature::SetLuminances) => "set_luminances",
+        WEnum::Value(wp_color_manager_v1::Feature::SetMasteringDisplayPrimaries) => {
+            "set_mastering_display_primaries"
+        }
+        WEnum::Value(wp_color_manager_v1::Feature::ExtendedTargetVolume) => {
+            "extended_target_volume"
+        }
+        WEnum::Value(wp_color_manager_v1::Feature::WindowsScrgb) => "windows_scrgb",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+// Convert a named transfer function to its protocol name
+pub(crate) fn transfer_function_to_string(
+    tf: WEnum<wp_color_manager_v1::TransferFunction>,
+) -> String {
+    match tf {
+        WEnum::Value(wp_color_manager_v1::TransferFunction::Bt1886) => "bt1886",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::Gamma22) => "gamma22",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::Gamma28) => "gamma28",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::St240) => "st240",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::ExtLinear) => "ext_linear",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::Log100) => "log_100",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::Log316) => "log_316",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::Xvycc) => "xvycc",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::Srgb) => "srgb",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::ExtSrgb) => "ext_srgb",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::St2084Pq) => "st2084_pq",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::St428) => "st428",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::Hlg) => "hlg",
+        WEnum::Value(wp_color_manager_v1::TransferFunction::CompoundPower24) => {
+            "compound_power_2_4"
+        }
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+// Convert named primaries to their protocol name
+pub(crate) fn primaries_to_string(primaries: WEnum<wp_color_manager_v1::Primaries>) -> String {
+    match primaries {
+        WEnum::Value(wp_color_manager_v1::Primaries::Srgb) => "srgb",
+        WEnum::Value(wp_color_manager_v1::Primaries::PalM) => "pal_m",
+        WEnum::Value(wp_color_manager_v1::Primaries::Pal) => "pal",
+        WEnum::Value(wp_color_manager_v1::Primaries::Ntsc) => "ntsc",
+        WEnum::Value(wp_color_manager_v1::Primaries::GenericFilm) => "generic_film",
+        WEnum::Value(wp_color_manager_v1::Primaries::Bt2020) => "bt2020",
+        WEnum::Value(wp_color_manager_v1::Primaries::Cie1931Xyz) => "cie1931_xyz",
+        WEnum::Value(wp_color_manager_v1::Primaries::DciP3) => "dci_p3",
+        WEnum::Value(wp_color_manager_v1::Primaries::DisplayP3) => "display_p3",
+        WEnum::Value(wp_color_manager_v1::Primaries::AdobeRgb) => "adobe_rgb",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+// Convert an image description failure cause to its protocol name
+pub(crate) fn cause_to_string(cause: WEnum<wp_image_description_v1::Cause>) -> String {
+    match cause {
+        WEnum::Value(wp_image_description_v1::Cause::LowVersion) => "low_version",
+        WEnum::Value(wp_image_description_v1::Cause::Unsupported) => "unsupported",
+        WEnum::Value(wp_image_description_v1::Cause::OperatingSystem) => "operating_system",
+        WEnum::Value(wp_image_description_v1::Cause::NoOutput) => "no_output",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+// Handle wp_color_manager_v1 events
+impl Dispatch<WpColorManagerV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _manager: &WpColorManagerV1,
+        event: wp_color_manager_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::ColorManager { manager_index } = data {
+            state.mark_event();
+            let Some(manager) = state.color_managers.get_mut(*manager_index) else {
+                return;
+            };
+            match event {
+                wp_color_manager_v1::Event::SupportedIntent { render_intent } => {
+                    manager.intents.push(render_intent_to_string(render_intent));
+                }
+                wp_color_manager_v1::Event::SupportedFeature { feature } => {
+                    manager.features.push(feature_to_string(feature));
+                }
+                wp_color_manager_v1::Event::SupportedTfNamed { tf } => {
+                    manager
+                        .transfer_functions
+                        .push(transfer_function_to_string(tf));
+                    if let WEnum::Value(tf) = tf {
+                        manager.named_transfer_functions.push(tf);
+                    }
+                }
+                wp_color_manager_v1::Event::SupportedPrimariesNamed { primaries } => {
+                    manager.primaries.push(primaries_to_string(primaries));
+                    if let WEnum::Value(primaries) = primaries {
+                        manager.named_primaries.push(primaries);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// Handle wp_color_management_output_v1 events
+impl Dispatch<WpColorManagementOutputV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _color_output: &WpColorManagementOutputV1,
+        _event: wp_color_management_output_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // image_description_changed only matters to clients that keep rendering.
+        if let UserData::ColorManagementOutput { .. } = data {
+            state.mark_event();
+        }
+    }
+}
+
+// Handle wp_image_description_creator_params_v1 events
+impl Dispatch<WpImageDescriptionCreatorParamsV1, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _creator: &WpImageDescriptionCreatorParamsV1,
+        _event: wp_image_description_creator_params_v1::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // The parametric creator has no events; the result arrives on the image description.
+    }
+}
+
+// Handle wp_image_description_v1 events
+impl Dispatch<WpImageDescriptionV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        description: &WpImageDescriptionV1,
+        event: wp_image_description_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::ColorProbe { probe_index } = data {
+            state.mark_event();
+            let Some(result) = state.color_probe_results.get_mut(*probe_index) else {
+                return;
+            };
+            match event {
+                wp_image_description_v1::Event::Ready { .. }
+                | wp_image_description_v1::Event::Ready2 { .. } => {
+                    result.status = "ready".to_string();
+                }
+                wp_image_description_v1::Event::Failed { cause, msg } => {
+                    result.status = "failed".to_string();
+                    result.failure = Some(format!("{}: {}", cause_to_string(cause), msg));
+                }
+                _ => {}
+            }
+            return;
+        }
+        if let UserData::ImageDescription { output_index } = data {
+            state.mark_event();
+            let identity = match event {
+                wp_image_description_v1::Event::Ready { identity } => identity as u64,
+                wp_image_description_v1::Event::Ready2 {
+                    identity_hi,
+                    identity_lo,
+                } => ((identity_hi as u64) << 32) | identity_lo as u64,
+                wp_image_description_v1::Event::Failed { cause, msg } => {
+                    if let Some(info) = state.output_image_description_mut(*output_index) {
+                        info.state = "failed".to_string();
+                        info.failure = Some(format!("{}: {}", cause_to_string(cause), msg));
+                    }
+                    return;
+                }
+                _ => return,
+            };
+            if let Some(info) = state.output_image_description_mut(*output_index) {
+                info.state = "ready".to_string();
+                info.identity = Some(identity);
+            }
+            description.get_information(
+                qh,
+                UserData::ImageDescriptionInfo {
+                    output_index: *output_index,
+                },
+            );
+        }
+    }
+}
+
+// Handle wp_image_description_info_v1 events
+impl Dispatch<WpImageDescriptionInfoV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _info: &WpImageDescriptionInfoV1,
+        event: wp_image_description_info_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::ImageDescriptionInfo { output_index } = data {
+            state.mark_event();
+            let Some(info) = state.output_image_description_mut(*output_index) else {
+                return;
+            };
+            match event {
+                wp_image_description_info_v1::Event::IccFile { icc, icc_size } => {
+                    info.icc_data = read_icc_file(icc, icc_size as usize).unwrap_or_default();
+                    info.icc_description = profile_description(&info.icc_data);
+                    info.icc_size = Some(icc_size);
+                }
+                wp_image_description_info_v1::Event::Primaries {
+                    r_x,
+                    r_y,
+                    g_x,
+                    g_y,
+                    b_x,
+                    b_y,
+                    w_x,
+                    w_y,
+                } => {
+                    info.primaries = Some(Chromaticities::from_protocol([
+                        r_x, r_y, g_x, g_y, b_x, b_y, w_x, w_y,
+                    ]));
+                }
+                wp_image_description_info_v1::Event::PrimariesNamed { primaries } => {
+                    info.primaries_named = Some(primaries_to_string(primaries));
+                }
+                wp_image_description_info_v1::Event::TfPower { eexp } => {
+                    info.tf_power = Some(eexp as f64 / 10_000.0);
+                }
+                wp_image_description_info_v1::Event::TfNamed { tf } => {
+                    info.tf_named = Some(transfer_function_to_string(tf));
+                }
+                wp_image_description_info_v1::Event::Luminances {
+                    min_lum,
+                    max_lum,
+                    reference_lum,
+                } => {
+                    info.luminances = Some(Luminances::from_protocol(
+                        min_lum,
+                        max_lum,
+                        Some(reference_lum),
+                    ));
+                }
+                wp_image_description_info_v1::Event::TargetPrimaries {
+                    r_x,
+                    r_y,
+                    g_x,
+                    g_y,
+                    b_x,
+                    b_y,
+                    w_x,
+                    w_y,
+                } => {
+                    info.target_primaries = Some(Chromaticities::from_protocol([
+                        r_x, r_y, g_x, g_y, b_x, b_y, w_x, w_y,
+                    ]));
+                }
+                wp_image_description_info_v1::Event::TargetLuminance { min_lum, max_lum } => {
+                    info.target_luminance = Some(Luminances::from_protocol(min_lum, max_lum, None));
+                }
+                wp_image_description_info_v1::Event::TargetMaxCll { max_cll } => {
+                    info.target_max_cll = Some(max_cll);
+                }
+                wp_image_description_info_v1::Event::TargetMaxFall { max_fall } => {
+                    info.target_max_fall = Some(max_fall);
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bt2020_primaries_scale_from_millionths() {
+        // ITU-R BT.2020 primaries with a D65 white point, as the compositor sends them
+        let primaries = Chromaticities::from_protocol([
+            708_000, 292_000, 170_000, 797_000, 131_000, 46_000, 312_700, 329_000,
+        ]);
+        assert_eq!(primaries.red, [0.708, 0.292]);
+        assert_eq!(primaries.green, [0.17, 0.797]);
+        assert_eq!(primaries.blue, [0.131, 0.046]);
+        assert_eq!(primaries.white, [0.3127, 0.329]);
+    }
+
+    #[test]
+    fn minimum_luminance_is_in_ten_thousandths() {
+        // PQ reference display: 0.005 to 10000 cd/m2 with a 203 cd/m2 reference white
+        let luminances = Luminances::from_protocol(50, 10_000, Some(203));
+        assert_eq!(luminances.min, 0.005);
+        assert_eq!(luminances.max, 10_000.0);
+        assert_eq!(luminances.reference, Some(203));
+
+        let target = Luminances::from_protocol(2_000, 400, None);
+        assert_eq!((target.min, target.max), (0.2, 400.0));
+        assert!(target.reference.is_none());
+    }
+
+    #[test]
+    fn unknown_enum_values_are_not_guessed() {
+        assert_eq!(feature_to_string(WEnum::Unknown(99)), "unknown");
+        assert_eq!(
+            feature_to_string(WEnum::Value(wp_color_manager_v1::Feature::SetLuminances)),
+            "set_luminances"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use serde::Serialize;
+use wayland_client::{Connection, Dispatch, QueueHandle, WEnum};
+use wayland_protocols::wp::color_representation::v1::client::{
+    wp_color_representation_manager_v1::{self, WpColorRepresentationManagerV1},
+    wp_color_representation_surface_v1::{AlphaMode, Coefficients, Range},
+};
+
+use crate::app::{AppData, UserData};
+
+// Color representation manager info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ColorRepresentationInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) alpha_modes: Vec<String>,
+    pub(crate) coefficients_and_ranges: Vec<CoefficientsAndRange>,
+}
+
+// Supported matrix coefficients and quantization range pair
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoefficientsAndRange {
+    pub(crate) coefficients: String,
+    pub(crate) range: String,
+}
+
+impl AppData {
+    pub(crate) fn add_color_representation(&mut self, name: u32) {
+        self.color_representation_info
+            .push(ColorRepresentationInfo {
+                name,
+                alpha_modes: Vec::new(),
+                coefficients_and_ranges: Vec::new(),
+            });
+    }
+}
+
+fn alpha_mode_to_string(alpha_mode: WEnum<AlphaMode>) -> String {
+    match alpha_mode {
+        WEnum::Value(AlphaMode::PremultipliedElectrical) => "premultiplied_electrical",
+        WEnum::Value(AlphaMode::PremultipliedOptical) => "premultiplied_optical",
+        WEnum::Value(AlphaMode::Straight) => "straight",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+fn coefficients_to_string(coefficients: WEnum<Coefficients>) -> String {
+    match coefficients {
+        WEnum::Value(Coefficients::Identity) => "identity",
+        WEnum::Value(Coefficients::Bt709) => "bt709",
+        WEnum::Value(Coefficients::Fcc) => "fcc",
+        WEnum::Value(Coefficients::Bt601) => "bt601",
+        WEnum::Value(Coefficients::Smpte240) => "smpte240",
+        WEnum::Value(Coefficients::Bt2020) => "bt2020",
+        WEnum::Value(Coefficients::Bt2020Cl) => "bt2020_cl",
+        WEnum::Value(Coefficients::Ictcp) => "ictcp",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+fn range_to_string(range: WEnum<Range>) -> String {
+    match range {
+        WEnum::Value(Range::Full) => "full",
+        WEnum::Value(Range::Limited) => "limited",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+// Handle wp_color_representation_manager_v1 events
+impl Dispatch<WpColorRepresentationManagerV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _manager: &WpColorRepresentationManagerV1,
+        event: wp_color_representation_manager_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::ColorRepresentation {
+            representation_index,
+        } = data
+        {
+            state.mark_event();
+            let Some(info) = state
+                .color_representation_info
+                .get_mut(*representation_index)
+            else {
+                return;
+            };
+            match event {
+                wp_color_representation_manager_v1::Event::SupportedAlphaMode { alpha_mode } => {
+                    info.alpha_modes.push(alpha_mode_to_string(alpha_mode));
+                }
+                wp_color_representation_manager_v1::Event::SupportedCoefficientsAndRanges {
+                    coefficients,
+                    range,
+                } => {
+                    info.coefficients_and_ranges.push(CoefficientsAndRange {
+                        coefficients: coefficients_to_string(coefficients),
+                        range: range_to_string(range),
+                    });
+                }
+                wp_color_representation_manager_v1::Event::Done => {}
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Decode as the Dispatch impl does, starting from the raw wire value
+    fn wire<T: TryFrom<u32>>(value: u32) -> WEnum<T> {
+        WEnum::from(value)
+    }
+
+    #[test]
+    fn coefficients_and_range_pairs_decode_from_wire_values() {
+        assert_eq!(coefficients_to_string(wire(2)), "bt709");
+        assert_eq!(coefficients_to_string(wire(7)), "bt2020_cl");
+        assert_eq!(range_to_string(wire(1)), "full");
+        assert_eq!(range_to_string(wire(2)), "limited");
+        assert_eq!(coefficients_to_string(wire(0)), "unknown");
+    }
+
+    #[test]
+    fn alpha_modes_decode_from_wire_values() {
+        assert_eq!(alpha_mode_to_string(wire(0)), "premultiplied_electrical");
+        assert_eq!(alpha_mode_to_string(wire(2)), "straight");
+        assert_eq!(alpha_mode_to_string(wire(3)), "unknown");
+    }
+}
@@ -1,6 +1,19 @@
 use serde::Serialize;
+use std::fs::File;
+use std::os::fd::OwnedFd;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::path::Path;
+use wayland_client::{event_created_child, Connection, Dispatch, QueueHandle};
+use wayland_protocols::wp::drm_lease::v1::client::{
+    wp_drm_lease_connector_v1::{self, WpDrmLeaseConnectorV1},
+    wp_drm_lease_device_v1::{self, WpDrmLeaseDeviceV1},
+};
 
-use crate::app::AppData;
+use crate::app::{AppData, UserData};
+use crate::drm_device::DrmDevice;
+
+// Directory holding the DRM device nodes
+const DRM_DEVICE_DIR: &str = "/dev/dri";
 
 // DRM lease device info structure
 #[derive(Debug, Clone, Serialize)]
@@ -9,6 +22,7 @@ pub struct DrmLeaseDeviceInfo {
     #[serde(skip_serializing)]
     pub(crate) name: u32,
     pub(crate) device_path: Option<String>,
+    pub(crate) device: Option<DrmDevice>,
     pub(crate) connectors: Vec<DrmLeaseConnectorInfo>,
 }
 
@@ -16,9 +30,12 @@ pub struct DrmLeaseDeviceInfo {
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct DrmLeaseConnectorInfo {
+    #[serde(skip_serializing)]
+    pub(crate) connector: WpDrmLeaseConnectorV1,
     pub(crate) name: String,
     pub(crate) description: String,
     pub(crate) connector_id: u32,
+    pub(crate) withdrawn: bool,
 }
 
 impl AppData {
@@ -26,33 +43,156 @@ impl AppData {
         self.drm_lease_devices.push(DrmLeaseDeviceInfo {
             name,
             device_path: None,
+            device: None,
             connectors: Vec::new(),
         });
     }
 
-    // TODO: Implement handling of drm_lease_device events (e.g., done, connector, etc.)
-    #[allow(dead_code)]
-    pub(crate) fn update_drm_lease_device_path(&mut self, device_index: usize, path: String) {
+    pub(crate) fn update_drm_lease_device_fd(&mut self, device_index: usize, fd: OwnedFd) {
+        let Ok(metadata) = File::from(fd).metadata() else {
+            return;
+        };
+        let rdev = metadata.rdev();
+        let path = drm_rdev_to_path(Path::new(DRM_DEVICE_DIR), rdev);
+        let drm_device = DrmDevice::from_dev_t(rdev, &self.sysfs_root);
         if let Some(device) = self.drm_lease_devices.get_mut(device_index) {
-            device.device_path = Some(path);
+            device.device_path = path;
+            device.device = Some(drm_device);
         }
     }
 
-    // TODO: Implement handling of drm_lease_device events (e.g., done, connector, etc.)
-    #[allow(dead_code)]
     pub(crate) fn add_drm_lease_connector(
         &mut self,
         device_index: usize,
-        name: String,
-        description: String,
-        connector_id: u32,
+        connector: WpDrmLeaseConnectorV1,
     ) {
         if let Some(device) = self.drm_lease_devices.get_mut(device_index) {
             device.connectors.push(DrmLeaseConnectorInfo {
-                name,
-                description,
-                connector_id,
+                connector,
+                name: String::new(),
+                description: String::new(),
+                connector_id: 0,
+                withdrawn: false,
             });
         }
     }
+
+    fn drm_lease_connector_mut(
+        &mut self,
+        connector: &WpDrmLeaseConnectorV1,
+    ) -> Option<&mut DrmLeaseConnectorInfo> {
+        self.drm_lease_devices
+            .iter_mut()
+            .flat_map(|device| device.connectors.iter_mut())
+            .find(|info| &info.connector == connector)
+    }
+}
+
+// Find the character device node under `dev_dir` matching a device number
+fn drm_rdev_to_path(dev_dir: &Path, rdev: u64) -> Option<String> {
+    std::fs::read_dir(dev_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .find(|entry| {
+            entry
+                .metadata()
+                .map(|metadata| metadata.file_type().is_char_device() && metadata.rdev() == rdev)
+                .unwrap_or(false)
+        })
+        .map(|entry| entry.path().display().to_string())
+}
+
+// Handle wp_drm_lease_device_v1 events
+impl Dispatch<WpDrmLeaseDeviceV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        device: &WpDrmLeaseDeviceV1,
+        event: wp_drm_lease_device_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::DrmLeaseDevice { device_index } = data {
+            state.mark_event();
+            match event {
+                wp_drm_lease_device_v1::Event::DrmFd { fd } => {
+                    state.update_drm_lease_device_fd(*device_index, fd);
+                }
+                wp_drm_lease_device_v1::Event::Connector { id } => {
+                    state.add_drm_lease_connector(*device_index, id);
+                }
+                wp_drm_lease_device_v1::Event::Done => {
+                    // The initial state is complete; nothing is leased, so let go of the device.
+                    device.release();
+                }
+                wp_drm_lease_device_v1::Event::Released => {}
+                _ => {}
+            }
+        }
+    }
+
+    event_created_child!(AppData, WpDrmLeaseDeviceV1, [
+        wp_drm_lease_device_v1::EVT_CONNECTOR_OPCODE => (WpDrmLeaseConnectorV1, UserData::DrmLeaseConnector),
+    ]);
+}
+
+// Handle wp_drm_lease_connector_v1 events
+impl Dispatch<WpDrmLeaseConnectorV1, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        connector: &WpDrmLeaseConnectorV1,
+        event: wp_drm_lease_connector_v1::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::DrmLeaseConnector = data {
+            state.mark_event();
+            let Some(info) = state.drm_lease_connector_mut(connector) else {
+                return;
+            };
+            match event {
+                wp_drm_lease_connector_v1::Event::Name { name } => {
+                    info.name = name;
+                }
+                wp_drm_lease_connector_v1::Event::Description { description } => {
+                    info.description = description;
+                }
+                wp_drm_lease_connector_v1::Event::ConnectorId { connector_id } => {
+                    info.connector_id = connector_id;
+                }
+                wp_drm_lease_connector_v1::Event::Withdrawn => {
+                    info.withdrawn = true;
+                }
+                wp_drm_lease_connector_v1::Event::Done => {}
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn finds_the_node_with_a_matching_device_number() {
+        let rdev = fs::metadata("/dev/null").unwrap().rdev();
+        assert_eq!(
+            drm_rdev_to_path(Path::new("/dev"), rdev).as_deref(),
+            Some("/dev/null")
+        );
+    }
+
+    // Regular files report a device number of 0 and must never match
+    #[test]
+    fn ignores_entries_that_are_not_character_devices() {
+        let dir = std::env::temp_dir().join(format!("wayland-info-dri-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("card0"), b"").unwrap();
+        let path = drm_rdev_to_path(&dir, 0);
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(path, None);
+    }
 }
@@ -0,0 +1,555 @@
+use serde::Serialize;
+use wayland_client::{event_created_child, Connection, Dispatch, QueueHandle, WEnum};
+use wayland_protocols::wp::tablet::zv2::client::{
+    zwp_tablet_manager_v2::{self, ZwpTabletManagerV2},
+    zwp_tablet_pad_dial_v2::{self, ZwpTabletPadDialV2},
+    zwp_tablet_pad_group_v2::{self, ZwpTabletPadGroupV2},
+    zwp_tablet_pad_ring_v2::{self, ZwpTabletPadRingV2},
+    zwp_tablet_pad_strip_v2::{self, ZwpTabletPadStripV2},
+    zwp_tablet_pad_v2::{self, ZwpTabletPadV2},
+    zwp_tablet_seat_v2::{self, ZwpTabletSeatV2},
+    zwp_tablet_tool_v2::{self, ZwpTabletToolV2},
+    zwp_tablet_v2::{self, ZwpTabletV2},
+};
+
+use crate::app::{AppData, UserData};
+
+// Tablet manager info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabletManagerInfo {
+    #[serde(skip_serializing)]
+    pub(crate) name: u32,
+    pub(crate) seats: Vec<TabletSeatInfo>,
+}
+
+// Tablet seat info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabletSeatInfo {
+    #[serde(skip_serializing)]
+    pub(crate) seat_index: usize,
+    pub(crate) seat: String,
+    pub(crate) tablets: Vec<TabletInfo>,
+    pub(crate) tools: Vec<TabletToolInfo>,
+    pub(crate) pads: Vec<TabletPadInfo>,
+}
+
+// Tablet info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabletInfo {
+    #[serde(skip_serializing)]
+    pub(crate) tablet: ZwpTabletV2,
+    pub(crate) name: String,
+    pub(crate) vid: Option<u32>,
+    pub(crate) pid: Option<u32>,
+    pub(crate) paths: Vec<String>,
+    pub(crate) bustype: Option<String>,
+}
+
+// Tablet tool info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabletToolInfo {
+    #[serde(skip_serializing)]
+    pub(crate) tool: ZwpTabletToolV2,
+    pub(crate) tool_type: String,
+    pub(crate) hardware_serial: Option<String>,
+    pub(crate) hardware_id_wacom: Option<String>,
+    pub(crate) capabilities: Vec<String>,
+}
+
+// Tablet pad info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabletPadInfo {
+    #[serde(skip_serializing)]
+    pub(crate) pad: ZwpTabletPadV2,
+    pub(crate) paths: Vec<String>,
+    pub(crate) buttons: u32,
+    pub(crate) groups: Vec<TabletPadGroupInfo>,
+}
+
+// Tablet pad group info structure
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabletPadGroupInfo {
+    #[serde(skip_serializing)]
+    pub(crate) group: ZwpTabletPadGroupV2,
+    pub(crate) buttons: Vec<u32>,
+    pub(crate) rings: u32,
+    pub(crate) strips: u32,
+    pub(crate) dials: u32,
+    pub(crate) modes: u32,
+}
+
+impl AppData {
+    pub(crate) fn add_tablet_manager(&mut self, name: u32) {
+        self.tablet_managers.push(TabletManagerInfo {
+            name,
+            seats: Vec::new(),
+        });
+    }
+
+    /// Request a tablet seat from every tablet manager for every bound seat.
+    pub(crate) fn get_tablet_seats(&mut self, qh: &QueueHandle<AppData>) {
+        for (manager_index, manager) in self.tablet_manager_objects.iter().enumerate() {
+            for (seat_index, seat) in self.seat_objects.iter().enumerate() {
+                let _tablet_seat = manager.get_tablet_seat(
+                    seat,
+                    qh,
+                    UserData::TabletSeat {
+                        manager_index,
+                        seat_index,
+                    },
+                );
+                if let Some(info) = self.tablet_managers.get_mut(manager_index) {
+                    info.seats.push(TabletSeatInfo {
+                        seat_index,
+                        seat: String::new(),
+                        tablets: Vec::new(),
+                        tools: Vec::new(),
+                        pads: Vec::new(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Name each tablet seat after its `SeatInfo`.
+    /// Call once all events have been dispatched.
+    pub(crate) fn resolve_tablet_seats(&mut self) {
+        for manager in &mut self.tablet_managers {
+            for tablet_seat in &mut manager.seats {
+                if let Some(seat) = self.seats.get(tablet_seat.seat_index) {
+                    tablet_seat.seat = seat.seat_name.clone();
+                }
+            }
+        }
+    }
+
+    fn tablet_seat_mut(
+        &mut self,
+        manager_index: usize,
+        seat_index: usize,
+    ) -> Option<&mut TabletSeatInfo> {
+        self.tablet_managers
+            .get_mut(manager_index)?
+            .seats
+            .iter_mut()
+            .find(|seat| seat.seat_index == seat_index)
+    }
+
+    fn tablet_seats_mut(&mut self) -> impl Iterator<Item = &mut TabletSeatInfo> {
+        self.tablet_managers
+            .iter_mut()
+            .flat_map(|manager| manager.seats.iter_mut())
+    }
+
+    fn tablet_mut(&mut self, tablet: &ZwpTabletV2) -> Option<&mut TabletInfo> {
+        self.tablet_seats_mut()
+            .flat_map(|seat| seat.tablets.iter_mut())
+            .find(|info| &info.tablet == tablet)
+    }
+
+    fn tablet_tool_mut(&mut self, tool: &ZwpTabletToolV2) -> Option<&mut TabletToolInfo> {
+        self.tablet_seats_mut()
+            .flat_map(|seat| seat.tools.iter_mut())
+            .find(|info| &info.tool == tool)
+    }
+
+    fn tablet_pad_mut(&mut self, pad: &ZwpTabletPadV2) -> Option<&mut TabletPadInfo> {
+        self.tablet_seats_mut()
+            .flat_map(|seat| seat.pads.iter_mut())
+            .find(|info| &info.pad == pad)
+    }
+
+    fn tablet_pad_group_mut(
+        &mut self,
+        group: &ZwpTabletPadGroupV2,
+    ) -> Option<&mut TabletPadGroupInfo> {
+        self.tablet_seats_mut()
+            .flat_map(|seat| seat.pads.iter_mut())
+            .flat_map(|pad| pad.groups.iter_mut())
+            .find(|info| &info.group == group)
+    }
+}
+
+fn tool_type_to_string(tool_type: WEnum<zwp_tablet_tool_v2::Type>) -> String {
+    match tool_type {
+        WEnum::Value(zwp_tablet_tool_v2::Type::Pen) => "pen",
+        WEnum::Value(zwp_tablet_tool_v2::Type::Eraser) => "eraser",
+        WEnum::Value(zwp_tablet_tool_v2::Type::Brush) => "brush",
+        WEnum::Value(zwp_tablet_tool_v2::Type::Pencil) => "pencil",
+        WEnum::Value(zwp_tablet_tool_v2::Type::Airbrush) => "airbrush",
+        WEnum::Value(zwp_tablet_tool_v2::Type::Finger) => "finger",
+        WEnum::Value(zwp_tablet_tool_v2::Type::Mouse) => "mouse",
+        WEnum::Value(zwp_tablet_tool_v2::Type::Lens) => "lens",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+fn capability_to_string(capability: WEnum<zwp_tablet_tool_v2::Capability>) -> String {
+    match capability {
+        WEnum::Value(zwp_tablet_tool_v2::Capability::Tilt) => "tilt",
+        WEnum::Value(zwp_tablet_tool_v2::Capability::Pressure) => "pressure",
+        WEnum::Value(zwp_tablet_tool_v2::Capability::Distance) => "distance",
+        WEnum::Value(zwp_tablet_tool_v2::Capability::Rotation) => "rotation",
+        WEnum::Value(zwp_tablet_tool_v2::Capability::Slider) => "slider",
+        WEnum::Value(zwp_tablet_tool_v2::Capability::Wheel) => "wheel",
+        _ => "unknown",
+    }
+    .to_string()
+}
+
+fn bustype_to_string(bustype: WEnum<zwp_tablet_v2::Bustype>) -> String {
+    match bustype {
+        WEnum::Value(zwp_tablet_v2::Bustype::Usb) => "usb".to_string(),
+        WEnum::Value(zwp_tablet_v2::Bustype::Bluetooth) => "bluetooth".to_string(),
+        WEnum::Value(zwp_tablet_v2::Bustype::Virtual) => "virtual".to_string(),
+        WEnum::Value(zwp_tablet_v2::Bustype::Serial) => "serial".to_string(),
+        WEnum::Value(zwp_tablet_v2::Bustype::I2c) => "i2c".to_string(),
+        WEnum::Unknown(v) => format!("0x{:x}", v),
+        _ => "unknown".to_string(),
+    }
+}
+
+// Handle zwp_tablet_manager_v2 events
+impl Dispatch<ZwpTabletManagerV2, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _manager: &ZwpTabletManagerV2,
+        _event: zwp_tablet_manager_v2::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        // ZwpTabletManagerV2 has no events; devices are announced on each tablet seat.
+    }
+}
+
+// Handle zwp_tablet_seat_v2 events
+impl Dispatch<ZwpTabletSeatV2, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        _tablet_seat: &ZwpTabletSeatV2,
+        event: zwp_tablet_seat_v2::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::TabletSeat {
+            manager_index,
+            seat_index,
+        } = data
+        {
+            state.mark_event();
+            let Some(seat) = state.tablet_seat_mut(*manager_index, *seat_index) else {
+                return;
+            };
+            match event {
+                zwp_tablet_seat_v2::Event::TabletAdded { id } => {
+                    seat.tablets.push(TabletInfo {
+                        tablet: id,
+                        name: String::new(),
+                        vid: None,
+                        pid: None,
+                        paths: Vec::new(),
+                        bustype: None,
+                    });
+                }
+                zwp_tablet_seat_v2::Event::ToolAdded { id } => {
+                    seat.tools.push(TabletToolInfo {
+                        tool: id,
+                        tool_type: String::new(),
+                        hardware_serial: None,
+                        hardware_id_wacom: None,
+                        capabilities: Vec::new(),
+                    });
+                }
+                zwp_tablet_seat_v2::Event::PadAdded { id } => {
+                    seat.pads.push(TabletPadInfo {
+                        pad: id,
+                        paths: Vec::new(),
+                        buttons: 0,
+                        groups: Vec::new(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    event_created_child!(AppData, ZwpTabletSeatV2, [
+        zwp_tablet_seat_v2::EVT_TABLET_ADDED_OPCODE => (ZwpTabletV2, UserData::Tablet),
+        zwp_tablet_seat_v2::EVT_TOOL_ADDED_OPCODE => (ZwpTabletToolV2, UserData::TabletTool),
+        zwp_tablet_seat_v2::EVT_PAD_ADDED_OPCODE => (ZwpTabletPadV2, UserData::TabletPad),
+    ]);
+}
+
+// Handle zwp_tablet_v2 events
+impl Dispatch<ZwpTabletV2, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        tablet: &ZwpTabletV2,
+        event: zwp_tablet_v2::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::Tablet = data {
+            state.mark_event();
+            if let zwp_tablet_v2::Event::Removed = event {
+                for seat in state.tablet_seats_mut() {
+                    seat.tablets.retain(|info| &info.tablet != tablet);
+                }
+                tablet.destroy();
+                return;
+            }
+            let Some(info) = state.tablet_mut(tablet) else {
+                return;
+            };
+            match event {
+                zwp_tablet_v2::Event::Name { name } => {
+                    info.name = name;
+                }
+                zwp_tablet_v2::Event::Id { vid, pid } => {
+                    info.vid = Some(vid);
+                    info.pid = Some(pid);
+                }
+                zwp_tablet_v2::Event::Path { path } => {
+                    info.paths.push(path);
+                }
+                zwp_tablet_v2::Event::Bustype { bustype } => {
+                    info.bustype = Some(bustype_to_string(bustype));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// Handle zwp_tablet_tool_v2 events
+impl Dispatch<ZwpTabletToolV2, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        tool: &ZwpTabletToolV2,
+        event: zwp_tablet_tool_v2::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::TabletTool = data {
+            state.mark_event();
+            if let zwp_tablet_tool_v2::Event::Removed = event {
+                for seat in state.tablet_seats_mut() {
+                    seat.tools.retain(|info| &info.tool != tool);
+                }
+                tool.destroy();
+                return;
+            }
+            let Some(info) = state.tablet_tool_mut(tool) else {
+                return;
+            };
+            match event {
+                zwp_tablet_tool_v2::Event::Type { tool_type } => {
+                    info.tool_type = tool_type_to_string(tool_type);
+                }
+                zwp_tablet_tool_v2::Event::HardwareSerial {
+                    hardware_serial_hi,
+                    hardware_serial_lo,
+                } => {
+                    let serial = ((hardware_serial_hi as u64) << 32) | hardware_serial_lo as u64;
+                    info.hardware_serial = Some(format!("0x{:x}", serial));
+                }
+                zwp_tablet_tool_v2::Event::HardwareIdWacom {
+                    hardware_id_hi,
+                    hardware_id_lo,
+                } => {
+                    let id = ((hardware_id_hi as u64) << 32) | hardware_id_lo as u64;
+                    info.hardware_id_wacom = Some(format!("0x{:x}", id));
+                }
+                zwp_tablet_tool_v2::Event::Capability { capability } => {
+                    info.capabilities.push(capability_to_string(capability));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+// Handle zwp_tablet_pad_v2 events
+impl Dispatch<ZwpTabletPadV2, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        pad: &ZwpTabletPadV2,
+        event: zwp_tablet_pad_v2::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::TabletPad = data {
+            state.mark_event();
+            if let zwp_tablet_pad_v2::Event::Removed = event {
+                for seat in state.tablet_seats_mut() {
+                    seat.pads.retain(|info| &info.pad != pad);
+                }
+                pad.destroy();
+                return;
+            }
+            let Some(info) = state.tablet_pad_mut(pad) else {
+                return;
+            };
+            match event {
+                zwp_tablet_pad_v2::Event::Group { pad_group } => {
+                    info.groups.push(TabletPadGroupInfo {
+                        group: pad_group,
+                        buttons: Vec::new(),
+                        rings: 0,
+                        strips: 0,
+                        dials: 0,
+                        modes: 0,
+                    });
+                }
+                zwp_tablet_pad_v2::Event::Path { path } => {
+                    info.paths.push(path);
+                }
+                zwp_tablet_pad_v2::Event::Buttons { buttons } => {
+                    info.buttons = buttons;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    event_created_child!(AppData, ZwpTabletPadV2, [
+        zwp_tablet_pad_v2::EVT_GROUP_OPCODE => (ZwpTabletPadGroupV2, UserData::TabletPadGroup),
+    ]);
+}
+
+// Handle zwp_tablet_pad_group_v2 events
+impl Dispatch<ZwpTabletPadGroupV2, UserData> for AppData {
+    fn event(
+        state: &mut Self,
+        group: &ZwpTabletPadGroupV2,
+        event: zwp_tablet_pad_group_v2::Event,
+        data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+        if let UserData::TabletPadGroup = data {
+            state.mark_event();
+            let Some(info) = state.tablet_pad_group_mut(group) else {
+                return;
+            };
+            match event {
+                zwp_tablet_pad_group_v2::Event::Buttons { buttons } => {
+                    info.buttons = buttons
+                        .chunks_exact(4)
+                        .map(|c| u32::from_ne_bytes([c[0], c[1], c[2], c[3]]))
+                        .collect();
+                }
+                zwp_tablet_pad_group_v2::Event::Ring { .. } => {
+                    info.rings += 1;
+                }
+                zwp_tablet_pad_group_v2::Event::Strip { .. } => {
+                    info.strips += 1;
+                }
+                zwp_tablet_pad_group_v2::Event::Dial { .. } => {
+                    info.dials += 1;
+                }
+                zwp_tablet_pad_group_v2::Event::Modes { modes } => {
+                    info.modes = modes;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    event_created_child!(AppData, ZwpTabletPadGroupV2, [
+        zwp_tablet_pad_group_v2::EVT_RING_OPCODE => (ZwpTabletPadRingV2, UserData::TabletPadControl),
+        zwp_tablet_pad_group_v2::EVT_STRIP_OPCODE => (ZwpTabletPadStripV2, UserData::TabletPadControl),
+        zwp_tablet_pad_group_v2::EVT_DIAL_OPCODE => (ZwpTabletPadDialV2, UserData::TabletPadControl),
+    ]);
+}
+
+// Rings, strips and dials are only counted; their events describe user interaction
+impl Dispatch<ZwpTabletPadRingV2, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _ring: &ZwpTabletPadRingV2,
+        _event: zwp_tablet_pad_ring_v2::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpTabletPadStripV2, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _strip: &ZwpTabletPadStripV2,
+        _event: zwp_tablet_pad_strip_v2::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwpTabletPadDialV2, UserData> for AppData {
+    fn event(
+        _state: &mut Self,
+        _dial: &ZwpTabletPadDialV2,
+        _event: zwp_tablet_pad_dial_v2::Event,
+        _data: &UserData,
+        _conn: &Connection,
+        _qh: &QueueHandle<AppData>,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tool_capabilities_use_protocol_names() {
+        let names: Vec<String> = [
+            zwp_tablet_tool_v2::Capability::Tilt,
+            zwp_tablet_tool_v2::Capability::Pressure,
+            zwp_tablet_tool_v2::Capability::Wheel,
+        ]
+        .into_iter()
+        .map(|capability| capability_to_string(WEnum::Value(capability)))
+        .collect();
+        assert_eq!(names, ["tilt", "pressure", "wheel"]);
+        assert_eq!(capability_to_string(WEnum::Unknown(42)), "unknown");
+    }
+
+    #[test]
+    fn tool_types_use_protocol_names() {
+        assert_eq!(
+            tool_type_to_string(WEnum::Value(zwp_tablet_tool_v2::Type::Airbrush)),
+            "airbrush"
+        );
+        assert_eq!(
+            tool_type_to_string(WEnum::Value(zwp_tablet_tool_v2::Type::Lens)),
+            "lens"
+        );
+        assert_eq!(tool_type_to_string(WEnum::Unknown(0x200)), "unknown");
+    }
+
+    // Unknown bus types keep their raw BUS_* value from linux/input.h
+    #[test]
+    fn unknown_bustype_is_shown_in_hex() {
+        assert_eq!(
+            bustype_to_string(WEnum::Value(zwp_tablet_v2::Bustype::I2c)),
+            "i2c"
+        );
+        assert_eq!(bustype_to_string(WEnum::Unknown(0x1c)), "0x1c");
+    }
+}